@@ -0,0 +1,510 @@
+extern crate alloc;
+
+#[path = "src/nonos/line.rs"]
+mod line;
+#[path = "src/nonos/rating.rs"]
+mod rating;
+
+use line::{Cell, Contradiction, solve_line};
+use rating::difficulty;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+/// Puzzles (file name and number shown in game) that shipped with more than one
+/// solution, only warned about until their art is fixed
+const KNOWN_AMBIGUOUS: &[(&str, usize)] = &[
+    ("6x6", 11),
+    ("6x6", 12),
+    ("6x6", 13),
+    ("8x8", 7),
+    ("8x8", 11),
+    ("8x8", 21),
+    ("10x10", 1),
+    ("10x10", 6),
+    ("10x10", 7),
+    ("10x10", 8),
+    ("10x10", 10),
+    ("10x10", 11),
+    ("10x10", 12),
+    ("10x10", 18),
+    ("10x10", 21),
+    ("12x12", 5),
+    ("12x12", 6),
+    ("12x12", 12),
+    ("12x12", 15),
+    ("12x12", 21),
+    ("20x10", 10),
+    ("20x10", 11),
+    ("20x10", 12),
+    ("22x12", 2),
+    ("22x12", 3),
+    ("22x12", 7),
+    ("22x12", 8),
+    ("22x12", 9),
+    ("22x12", 10),
+];
+
+/// Solution counting stops once this many are found
+const SOLUTION_LIMIT: usize = 2;
+
+/// Largest board (width, height), `MAX_COLS` and `MAX_ROWS` in src/puzzle_size.rs
+const MAX_SIZE: (usize, usize) = (30, 25);
+
+/// Clues shown beside each row and above each column, `CLUES_PER_ROW` and `CLUES_PER_COL`
+/// in src/puzzle_size.rs
+const MAX_CLUES: usize = 8;
+
+/// A `.nonos` file, named `<width>x<height>.nonos`
+struct NonosFile {
+    name: String,
+    width: usize,
+    height: usize,
+    /// None if the puzzle isn't laid out correctly
+    grids: Vec<Option<Vec<Vec<bool>>>>,
+    /// 1 (easiest) to 5 for each puzzle, see [rating::difficulty]
+    difficulty: Vec<u8>,
+}
+
+impl NonosFile {
+    /// Module name
+    fn ident(&self) -> String {
+        format!("_{}", self.name)
+    }
+
+    /// Bytes needed to store one board at 2 bits per cell
+    fn progress_size(&self) -> usize {
+        (self.width * self.height).div_ceil(4)
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=assets");
+    let mut files = read_assets();
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    // puzzle numbers can't be trusted until calc::parse_grids accepts the layout
+    if files
+        .iter()
+        .all(|file| file.grids.iter().all(Option::is_some))
+    {
+        check_puzzles(&mut files, &out_dir);
+    }
+
+    fs::write(out_dir.join("nonos.rs"), nonos_modules(&files)).unwrap();
+}
+
+/// Finds every `assets/*.nonos`, ordered by area, which is the order sizes are saved in
+///
+/// Adding or removing a puzzle moves the save offsets of every later size, `save_migration.rs`
+/// fails the build until there's a new save version for it
+fn read_assets() -> Vec<NonosFile> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir("assets").expect("reading assets") {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "nonos") {
+            continue;
+        }
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+        let (width, height) = name
+            .split_once('x')
+            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+            .unwrap_or_else(|| panic!("{} must be named <width>x<height>", path.display()));
+        if width > MAX_SIZE.0 || height > MAX_SIZE.1 {
+            panic!(
+                "{} is larger than the biggest board, {}x{}",
+                path.display(),
+                MAX_SIZE.0,
+                MAX_SIZE.1
+            );
+        }
+
+        let text =
+            fs::read_to_string(&path).unwrap_or_else(|e| panic!("reading {}: {e}", path.display()));
+        let grids = parse_grids(&text, width, height);
+
+        files.push(NonosFile {
+            name,
+            width,
+            height,
+            difficulty: vec![1; grids.len()],
+            grids,
+        });
+    }
+    files.sort_by_key(|file| (file.width * file.height, file.width));
+    files
+}
+
+/// Fails the build for puzzles without exactly one solution, other than those in
+/// [KNOWN_AMBIGUOUS], and rates them all
+fn check_puzzles(files: &mut [NonosFile], out_dir: &Path) {
+    let mut report = String::new();
+    let mut failures = Vec::new();
+    let mut ambiguous = 0;
+
+    for file in files.iter_mut() {
+        for (idx, grid) in file.grids.iter().enumerate() {
+            let check = check_puzzle(grid.as_ref().unwrap(), file.width, file.height);
+            file.difficulty[idx] = difficulty(
+                check.passes,
+                check.guesses,
+                check.undetermined,
+                file.width * file.height,
+            );
+            let line = format!(
+                "{} #{}: {} solution(s), clue sums {}, {}, {} passes, {} undetermined, {} guesses, difficulty {}{}",
+                file.name,
+                idx + 1,
+                match check.solutions {
+                    n if n >= SOLUTION_LIMIT => format!("{n}+"),
+                    n => n.to_string(),
+                },
+                if check.sums_match { "match" } else { "differ" },
+                if check.line_solvable {
+                    "line solvable"
+                } else {
+                    "needs guessing"
+                },
+                check.passes,
+                check.undetermined,
+                check.guesses,
+                file.difficulty[idx],
+                if check.clues_fit {
+                    ""
+                } else {
+                    ", too many clues"
+                },
+            );
+            let known = KNOWN_AMBIGUOUS.contains(&(file.name.as_str(), idx + 1));
+            if known && check.solutions == 1 {
+                failures.push(format!("{line} (remove from KNOWN_AMBIGUOUS)"));
+            } else if known {
+                ambiguous += 1;
+            } else if check.solutions != 1 || !check.sums_match || !check.clues_fit {
+                failures.push(line.clone());
+            }
+            writeln!(report, "{line}").unwrap();
+        }
+    }
+
+    let out = out_dir.join("nonos_report.txt");
+    fs::write(&out, report).unwrap();
+
+    if ambiguous > 0 {
+        println!(
+            "cargo:warning={ambiguous} known puzzles have more than one solution, see {}",
+            out.display()
+        );
+    }
+
+    if !failures.is_empty() {
+        panic!(
+            "invalid puzzles:\n{}\nfull report: {}",
+            failures.join("\n"),
+            out.display()
+        );
+    }
+}
+
+/// Module per size with the puzzles, their clues and save offsets, plus totals for the
+/// save data
+fn nonos_modules(files: &[NonosFile]) -> String {
+    let mut output = String::new();
+
+    // offsets of each size in the completed flags and in progress store
+    let mut save_idx = 0;
+    let mut progress_idx = 0;
+
+    for file in files {
+        let images = if file.width == file.height {
+            "sq_nono_images"
+        } else {
+            "rect_nono_images"
+        };
+        writeln!(
+            output,
+            r#"pub mod {ident} {{
+    use crate::nonos::calc::{{Clues, Meta, ParsedGrids, compute_all_clues, parse_grids}};
+    use crate::puzzle_size::{{MAX_COLS, MAX_ROWS, Puzzle, PuzzleSet}};
+    use crate::{images};
+
+    const N: usize = {count};
+    const W: usize = {width};
+    const H: usize = {height};
+
+    const PARSED: ParsedGrids<N> = parse_grids::<N>(
+        include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/{name}.nonos")),
+        W,
+        H,
+    );
+
+    pub(in crate::nonos) const GAMES: [[[u8; MAX_COLS]; MAX_ROWS]; N] = PARSED.grids;
+
+    const META: [Meta; N] = PARSED.meta;
+
+    const CLUES: [Clues; N] = compute_all_clues(GAMES, W, H);
+
+    const UNIQUE: [bool; N] = {unique:?};
+
+    const DIFFICULTY: [u8; N] = {difficulty:?};
+
+    pub fn game(idx: usize) -> Puzzle {{
+        Puzzle {{
+            row_clues: CLUES[idx].rows,
+            col_clues: CLUES[idx].cols,
+            width: W,
+            height: H,
+            solution: CLUES[idx].solution,
+            unique: UNIQUE[idx],
+            title: META[idx].title,
+            author: META[idx].author,
+            difficulty: match META[idx].difficulty {{
+                Some(difficulty) => difficulty,
+                None => DIFFICULTY[idx],
+            }},
+            hidden: META[idx].hidden,
+        }}
+    }}
+
+    pub static SET: PuzzleSet = PuzzleSet {{
+        width: W,
+        height: H,
+        count: N,
+        save_idx: {save_idx},
+        progress_idx: {progress_idx},
+        images: &{images}::_{tag},
+        game,
+    }};
+}}
+"#,
+            ident = file.ident(),
+            count = file.grids.len(),
+            width = file.width,
+            height = file.height,
+            name = file.name,
+            tag = file.name.to_uppercase(),
+            unique = (1..=file.grids.len())
+                .map(|number| !KNOWN_AMBIGUOUS.contains(&(file.name.as_str(), number)))
+                .collect::<Vec<_>>(),
+            difficulty = file.difficulty,
+        )
+        .unwrap();
+
+        save_idx += file.grids.len();
+        progress_idx += file.grids.len() * file.progress_size();
+    }
+
+    writeln!(
+        output,
+        "/// Width, height and puzzle count of each size, in the order they're saved"
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "pub const SAVE_ORDER: &[(usize, usize, usize)] = &{:?};",
+        files
+            .iter()
+            .map(|file| (file.width, file.height, file.grids.len()))
+            .collect::<Vec<_>>()
+    )
+    .unwrap();
+    writeln!(output, "pub const TOTAL_GAME_COUNT: usize = {save_idx};").unwrap();
+    writeln!(
+        output,
+        "/// Bytes needed to store every in progress board at 2 bits per cell"
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "pub const TOTAL_PROGRESS_SIZE: usize = {progress_idx};"
+    )
+    .unwrap();
+
+    output
+}
+
+/// Splits the file into puzzles at blank lines, skipping the `key: value` headers at the
+/// start of each
+///
+/// Puzzles that aren't `width` x `height` are None, `calc::parse_grids` reports exactly
+/// where they're wrong when the game is compiled
+fn parse_grids(text: &str, width: usize, height: usize) -> Vec<Option<Vec<Vec<bool>>>> {
+    let mut blocks = vec![];
+    let mut block: Vec<&str> = vec![];
+    for line in text.lines().chain([""]) {
+        if line.is_empty() {
+            if !block.is_empty() {
+                blocks.push(block);
+                block = vec![];
+            }
+        } else if !(block.is_empty() && line.contains(':')) {
+            block.push(line);
+        }
+    }
+
+    blocks
+        .iter()
+        .map(|rows| {
+            if rows.len() != height {
+                return None;
+            }
+            rows.iter()
+                .map(|row| {
+                    if row.len() != width {
+                        return None;
+                    }
+                    row.bytes()
+                        .map(|b| match b {
+                            b'#' => Some(true),
+                            b'_' => Some(false),
+                            _ => None,
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Clues in the format used by the game, runs followed by a 0
+fn clues(line: impl Iterator<Item = bool>) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut run = 0;
+    for filled in line {
+        if filled {
+            run += 1;
+        } else if run > 0 {
+            output.push(run);
+            run = 0;
+        }
+    }
+    if run > 0 {
+        output.push(run);
+    }
+    output.push(0);
+    output
+}
+
+struct Check {
+    solutions: usize,
+    sums_match: bool,
+    /// No row or column has more than `MAX_CLUES` clues
+    clues_fit: bool,
+    line_solvable: bool,
+    /// Rounds of line solving every row and column that decided a cell
+    passes: usize,
+    /// Cells line solving alone leaves unknown
+    undetermined: usize,
+    /// Cells guessed while searching for solutions
+    guesses: usize,
+}
+
+fn check_puzzle(grid: &[Vec<bool>], width: usize, height: usize) -> Check {
+    let rows: Vec<Vec<u8>> = grid.iter().map(|row| clues(row.iter().copied())).collect();
+    let cols: Vec<Vec<u8>> = (0..width)
+        .map(|x| clues(grid.iter().map(|row| row[x])))
+        .collect();
+
+    let sum = |clues: &[Vec<u8>]| -> usize { clues.iter().flatten().map(|&c| c as usize).sum() };
+    let sums_match = sum(&rows) == sum(&cols);
+    // clues end with a 0
+    let clues_fit = rows
+        .iter()
+        .chain(&cols)
+        .all(|clues| clues.len() <= MAX_CLUES + 1);
+
+    let mut cells = vec![vec![Cell::Unknown; width]; height];
+    let passes = propagate(&rows, &cols, &mut cells);
+    let undetermined = cells
+        .iter()
+        .flatten()
+        .filter(|&&cell| cell == Cell::Unknown)
+        .count();
+    let line_solvable = passes.is_ok() && undetermined == 0;
+
+    let mut guesses = 0;
+    let solutions = count_solutions(
+        &rows,
+        &cols,
+        vec![vec![Cell::Unknown; width]; height],
+        &mut guesses,
+    );
+
+    Check {
+        solutions,
+        sums_match,
+        clues_fit,
+        line_solvable,
+        passes: passes.unwrap_or(0),
+        undetermined,
+        guesses,
+    }
+}
+
+/// Line solves every row and column until nothing changes
+///
+/// Returns the number of rounds that decided a cell
+fn propagate(
+    rows: &[Vec<u8>],
+    cols: &[Vec<u8>],
+    cells: &mut [Vec<Cell>],
+) -> Result<usize, Contradiction> {
+    let mut passes = 0;
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (clues, row) in rows.iter().zip(cells.iter_mut()) {
+            changed |= solve_line(clues, row)?;
+        }
+        for (x, clues) in cols.iter().enumerate() {
+            let mut col: Vec<Cell> = cells.iter().map(|row| row[x]).collect();
+            if solve_line(clues, &mut col)? {
+                for (row, cell) in cells.iter_mut().zip(col) {
+                    row[x] = cell;
+                }
+                changed = true;
+            }
+        }
+        if changed {
+            passes += 1;
+        }
+    }
+    Ok(passes)
+}
+
+/// Counts solutions up to [SOLUTION_LIMIT] by guessing cells that line solving can't decide
+///
+/// Adds the number of cells guessed to `guesses`
+fn count_solutions(
+    rows: &[Vec<u8>],
+    cols: &[Vec<u8>],
+    mut cells: Vec<Vec<Cell>>,
+    guesses: &mut usize,
+) -> usize {
+    if propagate(rows, cols, &mut cells).is_err() {
+        return 0;
+    }
+
+    let unknown = cells.iter().enumerate().find_map(|(y, row)| {
+        row.iter()
+            .position(|&cell| cell == Cell::Unknown)
+            .map(|x| (x, y))
+    });
+    let Some((x, y)) = unknown else {
+        return 1;
+    };
+
+    *guesses += 1;
+    let mut count = 0;
+    for guess in [Cell::Filled, Cell::Empty] {
+        let mut next = cells.clone();
+        next[y][x] = guess;
+        count += count_solutions(rows, cols, next, guesses);
+        if count >= SOLUTION_LIMIT {
+            break;
+        }
+    }
+    count
+}
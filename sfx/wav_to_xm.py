@@ -0,0 +1,115 @@
+"""Converts the music wavs to XM modules for agb_tracker
+
+    python3 sfx/wav_to_xm.py
+
+Each channel of the 16 bit stereo wav becomes an 8 bit looping sample, played from
+the first row on a channel panned fully to its side. The second pattern is empty and
+the song restarts on it, so the samples keep looping without being triggered again.
+"""
+
+import math
+import struct
+import wave
+from pathlib import Path
+
+TRACKS = ['menu', 'game']
+SFX = Path(__file__).parent
+
+# XM plays C-4 at 8363Hz, relative note and finetune move it to the wav's rate
+C4 = 49
+XM_RATE = 8363
+
+
+def name(text, length):
+    data = text.encode('ascii')[:length]
+    return data + bytes(length - len(data))
+
+
+def read_wav(path):
+    with wave.open(str(path)) as wav:
+        assert wav.getnchannels() == 2 and wav.getsampwidth() == 2, f'{path} isn\'t 16 bit stereo'
+        frames = wav.readframes(wav.getnframes())
+        rate = wav.getframerate()
+    values = struct.unpack(f'<{len(frames) // 2}h', frames)
+    left = bytes((v >> 8) & 0xFF for v in values[0::2])
+    right = bytes((v >> 8) & 0xFF for v in values[1::2])
+    return rate, left, right
+
+
+def tuning(rate):
+    semitones = 12 * math.log2(rate / XM_RATE)
+    relative_note = math.floor(semitones)
+    finetune = round((semitones - relative_note) * 128)
+    if finetune == 128:
+        relative_note, finetune = relative_note + 1, 0
+    return relative_note, finetune
+
+
+def instrument(label, data, relative_note, finetune):
+    header = bytearray()
+    header += name(label, 22)
+    header += bytes([0])  # type
+    header += struct.pack('<H', 1)  # samples
+    header += struct.pack('<I', 40)  # sample header size
+    header += bytes(96)  # sample for each note
+    header += bytes(48 + 48)  # volume and panning envelope points
+    header += bytes(8)  # envelope point counts, sustain and loop points
+    header += bytes([0, 0])  # envelopes off
+    header += bytes(4)  # vibrato
+    header += struct.pack('<H', 0)  # fadeout
+    header += bytes(22)
+    header = struct.pack('<I', len(header) + 4) + header
+
+    sample = struct.pack('<III', len(data), 0, len(data))
+    sample += struct.pack('<BbBBbB', 64, finetune, 1, 128, relative_note, 0)
+    sample += name(label, 22)
+
+    delta = bytearray()
+    previous = 0
+    for value in data:
+        delta.append((value - previous) & 0xFF)
+        previous = value
+    return header + sample + delta
+
+
+def pattern(cells):
+    data = bytearray()
+    for note, instrument_id, panning in cells:
+        # note, instrument, effect and parameter follow
+        data += bytes([0x80 | 0x1 | 0x2 | 0x8 | 0x10, note, instrument_id, 0x8, panning])
+    return struct.pack('<IBHH', 9, 0, 1, len(data)) + data
+
+
+def module(title, left, right, relative_note, finetune):
+    instruments = [instrument(f'{title} left', left, relative_note, finetune)]
+    if left == right:
+        right_id = 1
+    else:
+        instruments.append(instrument(f'{title} right', right, relative_note, finetune))
+        right_id = 2
+    patterns = [
+        pattern([(C4, 1, 0x00), (C4, right_id, 0xFF)]),
+        struct.pack('<IBHH', 9, 0, 1, 2) + bytes([0x80, 0x80]),
+    ]
+    order = [0, 1]
+
+    out = bytearray()
+    out += b'Extended Module: '
+    out += name(title, 20)
+    out += bytes([0x1A])
+    out += name('wav_to_xm.py', 20)
+    out += struct.pack('<H', 0x0104)
+    out += struct.pack('<I', 276)
+    # order length, restart position, channels, patterns, instruments, linear frequencies,
+    # speed and bpm
+    out += struct.pack('<HHHHHHHH', len(order), 1, 2, len(patterns), len(instruments), 1, 6, 125)
+    out += bytes(order) + bytes(256 - len(order))
+    for data in patterns + instruments:
+        out += data
+    return bytes(out)
+
+
+for track in TRACKS:
+    rate, left, right = read_wav(SFX / f'{track}.wav')
+    relative_note, finetune = tuning(rate)
+    (SFX / f'{track}.xm').write_bytes(module(track, left, right, relative_note, finetune))
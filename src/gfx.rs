@@ -1,8 +1,9 @@
 use crate::sprites;
 use agb::display::object::Object;
 use agb::display::tile_data::TileData;
-use agb::display::tiled::{RegularBackground, RegularBackgroundSize, TileFormat};
+use agb::display::tiled::{BackgroundId, RegularBackground, RegularBackgroundSize, TileFormat};
 use agb::display::{GraphicsFrame, Priority};
+use alloc::vec::Vec;
 
 pub const TILE_SIZE: i32 = 8;
 
@@ -119,6 +120,17 @@ pub fn background(data: &'static TileData, priority: Priority) -> RegularBackgro
     background
 }
 
+/// Shows each background, returning their ids for blending
+pub fn show_backgrounds<'a>(
+    backgrounds: impl IntoIterator<Item = &'a RegularBackground>,
+    graphics: &mut GraphicsFrame,
+) -> Vec<BackgroundId> {
+    backgrounds
+        .into_iter()
+        .map(|bg| bg.show(graphics).into())
+        .collect()
+}
+
 /// Background first
 /// then extras
 /// then ui
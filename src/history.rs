@@ -0,0 +1,111 @@
+use crate::scenes::scene_game_puzzle::Guess;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+/// Oldest strokes are dropped past this
+const MAX_STROKES: usize = 64;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CellChange {
+    pub pos: (usize, usize),
+    pub from: Guess,
+    pub to: Guess,
+}
+
+/// Undo/redo stack for a board, each entry is every cell changed while A or B was held
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct History {
+    undo: VecDeque<Vec<CellChange>>,
+    redo: Vec<Vec<CellChange>>,
+    stroke: Vec<CellChange>,
+}
+
+impl History {
+    /// Adds a change to the current stroke
+    pub fn record(&mut self, pos: (usize, usize), from: Guess, to: Guess) {
+        if from != to {
+            self.stroke.push(CellChange { pos, from, to });
+        }
+    }
+
+    /// Finishes the current stroke, making it the next thing to undo
+    pub fn end_stroke(&mut self) {
+        if self.stroke.is_empty() {
+            return;
+        }
+        if self.undo.len() == MAX_STROKES {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(core::mem::take(&mut self.stroke));
+        self.redo.clear();
+    }
+
+    /// Returns the changes to revert, newest first
+    pub fn undo(&mut self) -> Option<Vec<CellChange>> {
+        let stroke = self.undo.pop_back()?;
+        let changes = stroke.iter().rev().copied().collect();
+        self.redo.push(stroke);
+        Some(changes)
+    }
+
+    /// Returns the changes to reapply, oldest first
+    pub fn redo(&mut self) -> Option<Vec<CellChange>> {
+        let stroke = self.redo.pop()?;
+        let changes = stroke.clone();
+        self.undo.push_back(stroke);
+        Some(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_case]
+    fn drag_is_one_entry(_gba: &mut agb::Gba) {
+        let mut history = History::default();
+        history.record((0, 0), Guess::Empty, Guess::Filled);
+        history.record((1, 0), Guess::Empty, Guess::Filled);
+        history.record((2, 0), Guess::Marked, Guess::Filled);
+        history.end_stroke();
+
+        let undo = history.undo().unwrap();
+        assert_eq!(undo.len(), 3);
+        assert_eq!(undo[0].pos, (2, 0));
+        assert_eq!(undo[0].from, Guess::Marked);
+        assert!(history.undo().is_none());
+
+        let redo = history.redo().unwrap();
+        assert_eq!(redo[0].pos, (0, 0));
+        assert!(history.redo().is_none());
+    }
+
+    #[test_case]
+    fn new_stroke_clears_redo(_gba: &mut agb::Gba) {
+        let mut history = History::default();
+        history.record((0, 0), Guess::Empty, Guess::Filled);
+        history.end_stroke();
+        history.undo();
+        history.record((1, 1), Guess::Empty, Guess::Marked);
+        history.end_stroke();
+
+        assert!(history.redo().is_none());
+        assert_eq!(history.undo().unwrap()[0].pos, (1, 1));
+        assert!(history.undo().is_none());
+    }
+
+    #[test_case]
+    fn history_is_bounded(_gba: &mut agb::Gba) {
+        let mut history = History::default();
+        for x in 0..MAX_STROKES + 5 {
+            history.record((x, 0), Guess::Empty, Guess::Filled);
+            history.end_stroke();
+        }
+
+        let mut count = 0;
+        while history.undo().is_some() {
+            count += 1;
+        }
+        assert_eq!(count, MAX_STROKES);
+    }
+}
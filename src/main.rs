@@ -7,30 +7,50 @@
 mod button_highlight;
 mod direction;
 mod gfx;
+mod history;
 mod input;
+mod music;
 mod nonos;
 mod puzzle_size;
+mod save_backend;
+mod save_banks;
+mod save_migration;
+mod scene_stack;
 mod scenes;
 mod settings_button_highlight;
 mod settings_data;
 mod sfx;
+mod text;
+mod transition;
 
 extern crate alloc;
 
-use crate::puzzle_size::PuzzleSize;
+use crate::nonos::generator::MAX_SEED;
+use crate::puzzle_size::{Puzzle, PuzzleId, PuzzleSize};
+use crate::save_banks::SaveBanks;
+use crate::scene_stack::SceneStack;
 use crate::scenes::scene_confirm::ConfirmScene;
+use crate::scenes::scene_endless_menu::EndlessMenuScene;
+use crate::scenes::scene_game_fail::GameFailScene;
 use crate::scenes::scene_game_pause::GamePauseScene;
 use crate::scenes::scene_game_puzzle::{GamePuzzleScene, Guess};
 use crate::scenes::scene_game_win::GameWinScene;
 use crate::scenes::scene_menu::MainMenuScene;
-use crate::scenes::scene_puzzle_menu::PuzzleMenuScene;
+use crate::scenes::scene_puzzle_menu::{PuzzleMenuScene, PuzzleOrder};
+use crate::scenes::scene_save_error::{SaveErrorScene, SaveFailure, SaveRecovery};
 use crate::scenes::scene_settings::SettingsScene;
-use crate::settings_data::{HelpLevel, SAVE_DATA_SIZE, SettingsData};
-use crate::sfx::start_track;
+use crate::scenes::scene_statistics::StatisticsScene;
+use crate::settings_data::{HelpLevel, SettingsData};
+use crate::sfx::{Audio, MIXER_FREQUENCY};
+use crate::transition::{SceneTransition, Transition};
 use agb::display::GraphicsFrame;
+use agb::display::font::Font;
+use agb::display::tiled::BackgroundId;
+use agb::eprintln;
 use agb::input::ButtonController;
-use agb::sound::mixer::{ChannelId, Frequency, Mixer, SoundData};
-use agb::{include_aseprite, include_background_gfx, include_wav};
+use agb::sound::mixer::SoundData;
+use agb::{include_aseprite, include_background_gfx, include_font, include_wav};
+use agb_tracker::{Track, include_xm};
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 
@@ -38,8 +58,10 @@ static SFX_CURSOR: SoundData = include_wav!("sfx/cursor.wav");
 static SFX_POSITIVE: SoundData = include_wav!("sfx/positive.wav");
 static SFX_NEGATIVE: SoundData = include_wav!("sfx/negative.wav");
 static SFX_CONGRATS: SoundData = include_wav!("sfx/congrats.wav");
-static SFX_GAME: SoundData = include_wav!("sfx/game.wav");
-static SFX_MENU: SoundData = include_wav!("sfx/menu.wav");
+static MUSIC_GAME: Track = include_xm!("sfx/game.xm");
+static MUSIC_MENU: Track = include_xm!("sfx/menu.xm");
+
+static FONT: Font = include_font!("gfx/font/ark-pixel-10px-proportional-latin.ttf", 10);
 
 include_aseprite!(
     mod sprites,
@@ -49,6 +71,10 @@ include_aseprite!(
     "gfx/game/sprite/congrats.aseprite",
     "gfx/menu/sprite/numbers.aseprite",
     "gfx/menu/sprite/warning.aseprite",
+    "gfx/menu/sprite/in_progress.aseprite",
+    "gfx/menu/sprite/no_mistakes.aseprite",
+    "gfx/menu/sprite/difficulty.aseprite",
+    "gfx/game/sprite/heart.aseprite",
 );
 
 include_aseprite!(
@@ -57,6 +83,7 @@ include_aseprite!(
     "gfx/game/sprite/nono_8x8.aseprite",
     "gfx/game/sprite/nono_10x10.aseprite",
     "gfx/game/sprite/nono_12x12.aseprite",
+    "gfx/game/sprite/nono_25x25.aseprite",
 );
 
 include_aseprite!(
@@ -70,12 +97,14 @@ include_background_gfx!(
     main => deduplicate "gfx/menu/bg/main.aseprite",
     board_sq => deduplicate "gfx/menu/bg/square.aseprite",
     board_rect => deduplicate "gfx/menu/bg/rect.aseprite",
+    board_big => deduplicate "gfx/menu/bg/big.aseprite",
     menu_6x6 => deduplicate "gfx/menu/bg/6x6.aseprite",
     menu_8x8 => deduplicate "gfx/menu/bg/8x8.aseprite",
     menu_10x10 => deduplicate "gfx/menu/bg/10x10.aseprite",
     menu_12x12 => deduplicate "gfx/menu/bg/12x12.aseprite",
     menu_20x10 => deduplicate "gfx/menu/bg/20x10.aseprite",
     menu_22x12 => deduplicate "gfx/menu/bg/22x12.aseprite",
+    menu_25x25 => deduplicate "gfx/menu/bg/25x25.aseprite",
     settings => deduplicate "gfx/menu/bg/settings.aseprite",
     dots => deduplicate "gfx/menu/bg/dots_bak.aseprite",
     polka => deduplicate "gfx/menu/bg/polka.aseprite",
@@ -92,183 +121,365 @@ include_background_gfx!(
     grid_12x12 => deduplicate "gfx/game/bg/grid_12x12.aseprite",
     grid_20x10 => deduplicate "gfx/game/bg/grid_20x10.aseprite",
     grid_22x12 => deduplicate "gfx/game/bg/grid_22x12.aseprite",
+    grid_lines => "gfx/game/bg/grid_lines.aseprite",
     pause => deduplicate "gfx/game/bg/pause.aseprite",
     win => deduplicate "gfx/game/bg/win.aseprite",
     confirm => deduplicate "gfx/game/bg/confirm.aseprite",
+    fail => deduplicate "gfx/game/bg/fail.aseprite",
     pieces => deduplicate "gfx/game/bg/board_pieces.aseprite",
     delete_save => deduplicate "gfx/menu/bg/settings_delete_save.aseprite",
+    save_error => deduplicate "gfx/menu/bg/save_error.aseprite",
 );
 
-#[cfg(all(feature = "sram", feature = "flash64"))]
-compile_error!("Features `sram` and `flash64` are mutually exclusive. Enable only one.");
-
 #[agb::entry]
 fn main(mut gba: agb::Gba) -> ! {
-    #[cfg(feature = "sram")]
-    gba.save.init_sram();
-
-    #[cfg(feature = "flash64")]
-    gba.save.init_flash_64k();
-
-    let mut mixer = gba.mixer.mixer(Frequency::Hz18157);
-
-    let mut settings_data = match gba.save.access() {
-        Ok(mut save_data) => {
-            let mut save_bytes = [0_u8; SAVE_DATA_SIZE];
-            if let Err(e) = save_data.read(0, &mut save_bytes) {
-                panic!("Save read error: {:?}", e);
-            }
-            SettingsData::from_bytes(save_bytes)
-        }
-        Err(e) => {
-            panic!("Save access error: {:?} (access)", e);
+    let mut startup_action = None;
+    let mut save_banks = SaveBanks::new(save_backend::cart(gba.save));
+    let mut settings_data = match load_save(&mut save_banks) {
+        Ok(data) => data,
+        Err(failure) => {
+            startup_action = Some(SceneAction::SaveError(failure));
+            SettingsData::default()
         }
     };
+    let mut save_enabled = true;
+    //kept for the session, not saved
+    let mut puzzle_order = PuzzleOrder::default();
+    let mut endless_seed = 0;
 
-    let mut scene: Box<dyn Scene> =
-        MainMenuScene::new(settings_data.music_enabled, settings_data.sfx_enabled);
+    let mut audio = Audio::new(gba.mixer.mixer(MIXER_FREQUENCY), &settings_data);
+    let mut scenes = SceneStack::new(MainMenuScene::new());
 
     let mut gfx = gba.graphics.get();
     let mut button_controller = ButtonController::new();
 
-    let mut bgm = Some(start_track(&mut mixer, SFX_MENU, SceneMusic::Menu));
-
-    bgm = scene.init(bgm, &mut mixer);
+    scenes.top().init(&mut audio);
+    let mut transition: Option<SceneTransition> = None;
 
     loop {
         let mut frame = gfx.frame();
         button_controller.update();
+        settings_data.inc_play_time();
 
-        if let Some(result) = scene.update(&button_controller, &mut mixer) {
-            match result {
-                SceneAction::Win(size, idx, grid_enabled) => {
-                    if let Ok(mut save_data) = gba.save.access()
-                        && let Ok(mut writer) = save_data.prepare_write(0..SAVE_DATA_SIZE)
-                    {
+        //scenes aren't updated while transitioning, the action is run once the screen is covered
+        let mut action = match transition.as_mut() {
+            Some(transition) => transition.update(),
+            None => {
+                match startup_action
+                    .take()
+                    .or_else(|| scenes.top().update(&button_controller, &mut audio))
+                {
+                    Some(action) => match action.transition() {
+                        Some(effect) => {
+                            transition = Some(SceneTransition::new(effect, action));
+                            None
+                        }
+                        None => Some(action),
+                    },
+                    None => None,
+                }
+            }
+        };
+        if action.is_some() {
+            while let Some(result) = action.take() {
+                match result {
+                    SceneAction::Win(size, id, puzzle, grid_enabled, no_mistakes, seconds) => {
                         settings_data.grid_enabled.insert(size, grid_enabled);
-                        settings_data.set_completed(size, idx);
-                        if let Err(e) = writer.write(0, &settings_data.as_bytes()) {
-                            panic!("(win) Save write error: {:?}", e);
+                        settings_data.extend_streak();
+                        let previous_best = match id {
+                            PuzzleId::Set(idx) => {
+                                settings_data.set_completed(size, idx, no_mistakes);
+                                settings_data.clear_progress(size, idx);
+                                let previous_best = settings_data.best_time(size, idx);
+                                // the time before a resumed board was saved isn't known
+                                if let Some(seconds) = seconds {
+                                    settings_data.set_best_time(size, idx, seconds);
+                                }
+                                previous_best
+                            }
+                            PuzzleId::Endless(seed) => {
+                                endless_seed = if seed < MAX_SEED { seed + 1 } else { 0 };
+                                None
+                            }
+                        };
+                        scenes.replace(GameWinScene::new(
+                            size,
+                            id,
+                            &puzzle,
+                            seconds,
+                            previous_best,
+                        ));
+                        if save_enabled {
+                            action = write_save(&mut save_banks, &settings_data);
                         }
                     }
-                    scene = GameWinScene::new(size, idx, settings_data.music_enabled);
-                }
-                SceneAction::MainMenu => {
-                    scene =
-                        MainMenuScene::new(settings_data.music_enabled, settings_data.sfx_enabled);
-                }
-                SceneAction::SettingsClose(music, sfx, help_level) => {
-                    settings_data.music_enabled = music;
-                    settings_data.sfx_enabled = sfx;
-                    settings_data.help_level = help_level;
-                    if let Ok(mut save_data) = gba.save.access()
-                        && let Ok(mut writer) = save_data.prepare_write(0..SAVE_DATA_SIZE)
-                        && let Err(e) = writer.write(0, &settings_data.as_bytes())
-                    {
-                        panic!("(settings) Save write error: {:?}", e);
+                    SceneAction::Fail(size, id) => {
+                        settings_data.end_streak();
+                        if let Some(idx) = id.set_idx() {
+                            settings_data.clear_progress(size, idx);
+                        }
+                        scenes.replace(GameFailScene::new(size, id));
+                        if save_enabled {
+                            action = write_save(&mut save_banks, &settings_data);
+                        }
                     }
-                    scene =
-                        MainMenuScene::new(settings_data.music_enabled, settings_data.sfx_enabled);
-                }
-                SceneAction::PuzzleMenu(size) => {
-                    scene = PuzzleMenuScene::new(
-                        size,
-                        settings_data.is_completed_by_size(size),
-                        settings_data.music_enabled,
-                        settings_data.sfx_enabled,
-                    );
-                }
-                SceneAction::Game(size, idx) => {
-                    scene = GamePuzzleScene::new(
-                        size,
-                        idx,
-                        None,
-                        *settings_data
-                            .grid_enabled
-                            .get(&size)
-                            .unwrap_or_else(|| panic!("size missing: {size:?}")),
-                        settings_data.music_enabled,
-                        settings_data.sfx_enabled,
-                        settings_data.help_level,
-                    );
-                }
-                SceneAction::RestoreGame(size, idx, grid_enabled, game_data) => {
-                    scene = GamePuzzleScene::new(
-                        size,
-                        idx,
-                        Some(game_data),
-                        grid_enabled,
-                        settings_data.music_enabled,
-                        settings_data.sfx_enabled,
-                        settings_data.help_level,
-                    );
-                }
-                SceneAction::PauseMenu(size, idx, grid_enabled, game_data) => {
-                    scene = GamePauseScene::new(
-                        size,
-                        idx,
-                        grid_enabled,
-                        game_data,
-                        settings_data.sfx_enabled,
-                    );
-                }
-                SceneAction::Confirm(positive, negative) => {
-                    scene = ConfirmScene::new(positive, negative, settings_data.sfx_enabled);
-                }
-                SceneAction::Settings => {
-                    scene = SettingsScene::new(
-                        settings_data.music_enabled,
-                        settings_data.sfx_enabled,
-                        settings_data.help_level,
-                    );
-                }
-                SceneAction::DeleteSave => {
-                    if let Ok(mut save_data) = gba.save.access()
-                        && let Ok(mut writer) = save_data.prepare_write(0..SAVE_DATA_SIZE)
-                    {
+                    SceneAction::MainMenu => {
+                        scenes.replace(MainMenuScene::new());
+                    }
+                    SceneAction::Statistics => {
+                        scenes.replace(StatisticsScene::new(settings_data.statistics()));
+                    }
+                    SceneAction::SettingsClose(check_placement, lives_mode, help_level) => {
+                        settings_data.music_volume = audio.music_volume();
+                        settings_data.sfx_volume = audio.sfx_volume();
+                        settings_data.check_placement = check_placement;
+                        settings_data.lives_mode = lives_mode;
+                        settings_data.help_level = help_level;
+                        scenes.replace(MainMenuScene::new());
+                        if save_enabled {
+                            action = write_save(&mut save_banks, &settings_data);
+                        }
+                    }
+                    SceneAction::PuzzleOrder(size, order) => {
+                        puzzle_order = order;
+                        action = Some(SceneAction::PuzzleMenu(size));
+                    }
+                    SceneAction::PuzzleMenu(size) => {
+                        scenes.replace(PuzzleMenuScene::new(
+                            size,
+                            puzzle_order,
+                            settings_data.is_completed_by_size(size),
+                            settings_data.is_no_mistakes_by_size(size),
+                            settings_data.is_in_progress_by_size(size),
+                            settings_data.best_times_by_size(size),
+                        ));
+                    }
+                    SceneAction::EndlessMenu(size) => {
+                        scenes.replace(EndlessMenuScene::new(size, endless_seed));
+                    }
+                    SceneAction::Game(size, id) => {
+                        let (progress, lives_lost) = match id {
+                            PuzzleId::Set(idx) => (
+                                settings_data.progress(size, idx),
+                                settings_data.lives_lost(size, idx),
+                            ),
+                            PuzzleId::Endless(seed) => {
+                                endless_seed = seed;
+                                (None, 0)
+                            }
+                        };
+                        scenes.replace(GamePuzzleScene::new(
+                            size,
+                            id,
+                            progress,
+                            lives_lost,
+                            *settings_data
+                                .grid_enabled
+                                .get(&size)
+                                .unwrap_or_else(|| panic!("size missing: {size:?}")),
+                            settings_data.help_level,
+                            settings_data.check_placement,
+                            settings_data.lives_mode,
+                        ));
+                    }
+                    SceneAction::RestartGame(size, id) => {
+                        settings_data.end_streak();
+                        if let Some(idx) = id.set_idx() {
+                            settings_data.clear_progress(size, idx);
+                        }
+                        scenes.replace(GamePuzzleScene::new(
+                            size,
+                            id,
+                            None,
+                            0,
+                            *settings_data
+                                .grid_enabled
+                                .get(&size)
+                                .unwrap_or_else(|| panic!("size missing: {size:?}")),
+                            settings_data.help_level,
+                            settings_data.check_placement,
+                            settings_data.lives_mode,
+                        ));
+                        if save_enabled {
+                            action = write_save(&mut save_banks, &settings_data);
+                        }
+                    }
+                    SceneAction::PauseMenu(size, id, guesses, lives_lost) => {
+                        if let Some(idx) = id.set_idx() {
+                            settings_data.set_progress(size, idx, &guesses);
+                            settings_data.set_lives_lost(size, idx, lives_lost);
+                        }
+                        scenes.push(GamePauseScene::new(size, id));
+                        if save_enabled {
+                            action = write_save(&mut save_banks, &settings_data);
+                        }
+                    }
+                    SceneAction::Confirm(positive) => {
+                        scenes.push(ConfirmScene::new(positive));
+                    }
+                    SceneAction::Close(result) => {
+                        close_top(&mut scenes, result, &mut audio);
+                    }
+                    SceneAction::Settings => {
+                        scenes.replace(SettingsScene::new(
+                            settings_data.check_placement,
+                            settings_data.lives_mode,
+                            settings_data.help_level,
+                        ));
+                    }
+                    SceneAction::DeleteSave => {
                         settings_data.reset();
-                        if let Err(e) = writer.write(0, &settings_data.as_bytes()) {
-                            panic!("(reset) Save write error: {:?}", e);
+                        audio.use_settings(&settings_data);
+                        scenes.replace(MainMenuScene::new());
+                        if save_enabled {
+                            action = write_save(&mut save_banks, &settings_data);
                         }
-                        scene = MainMenuScene::new(
-                            settings_data.music_enabled,
-                            settings_data.sfx_enabled,
-                        );
                     }
+                    SceneAction::SaveError(failure) => {
+                        scenes.push(SaveErrorScene::new(failure));
+                    }
+                    SceneAction::SaveRecovery(recovery) => match recovery {
+                        SaveRecovery::Retry(SaveFailure::Read(_)) => {
+                            match load_save(&mut save_banks) {
+                                Ok(data) => {
+                                    settings_data = data;
+                                    audio.use_settings(&settings_data);
+                                    scenes.replace(MainMenuScene::new());
+                                }
+                                Err(failure) => {
+                                    close_top(&mut scenes, SceneResult::Back, &mut audio);
+                                    action = Some(SceneAction::SaveError(failure));
+                                }
+                            }
+                        }
+                        SaveRecovery::Retry(SaveFailure::Write(_)) => {
+                            //a new error scene is opened if it fails again
+                            close_top(&mut scenes, SceneResult::Back, &mut audio);
+                            action = write_save(&mut save_banks, &settings_data);
+                        }
+                        SaveRecovery::WithoutSaving => {
+                            save_enabled = false;
+                            close_top(&mut scenes, SceneResult::Back, &mut audio);
+                        }
+                        SaveRecovery::Reset => {
+                            save_enabled = true;
+                            settings_data.reset();
+                            audio.use_settings(&settings_data);
+                            scenes.replace(MainMenuScene::new());
+                            action = write_save(&mut save_banks, &settings_data);
+                        }
+                    },
                 }
             }
-            bgm = scene.init(bgm, &mut mixer);
+            scenes.top().init(&mut audio);
         }
 
-        scene.show(&mut frame);
+        let shown = scenes.top().show(&mut frame);
+        if let Some(active) = &transition {
+            active.darken(&mut frame, &shown);
+        }
 
-        mixer.frame();
+        audio.frame();
         frame.commit();
+
+        if let Some(active) = &transition {
+            active.apply_mosaic();
+            if active.is_finished() {
+                transition = None;
+            }
+        }
+    }
+}
+
+fn load_save(save_banks: &mut SaveBanks) -> Result<SettingsData, SaveFailure> {
+    SettingsData::load(save_banks).map_err(|e| {
+        eprintln!("Save read error: {:?}", e);
+        SaveFailure::Read(e.into())
+    })
+}
+
+/// Returns a [SceneAction::SaveError] if the write failed
+fn write_save(save_banks: &mut SaveBanks, settings_data: &SettingsData) -> Option<SceneAction> {
+    match settings_data.save(save_banks) {
+        Ok(()) => None,
+        Err(e) => {
+            eprintln!("Save write error: {:?}", e);
+            Some(SceneAction::SaveError(SaveFailure::Write(e.into())))
+        }
+    }
+}
+
+/// Closes the top scene, opening the main menu if nothing was under it
+fn close_top(scenes: &mut SceneStack, result: SceneResult, audio: &mut Audio) {
+    if !scenes.pop(result, audio) {
+        scenes.replace(MainMenuScene::new());
     }
 }
 
 trait Scene {
-    fn init(
-        &mut self,
-        bgm: Option<(SceneMusic, ChannelId)>,
-        mixer: &mut Mixer,
-    ) -> Option<(SceneMusic, ChannelId)>;
-    fn update(&mut self, buttons: &ButtonController, mixer: &mut Mixer) -> Option<SceneAction>;
-    fn show(&mut self, graphics: &mut GraphicsFrame);
+    /// Called each time the scene becomes the top one, including when resumed
+    fn init(&mut self, audio: &mut Audio);
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction>;
+    /// Returns the backgrounds shown, so transitions can darken them
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId>;
+    /// Called when another scene opens over this one, backgrounds can be dropped to free VRAM
+    fn suspend(&mut self) {}
+    /// Called when the scene over this one closes, before `init`
+    fn resume(&mut self, _result: SceneResult, _audio: &mut Audio) {}
+}
+
+/// Sent to the scene underneath when the one over it closes
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum SceneResult {
+    Back,
+    /// Mark the cells that don't match the answer
+    Check,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 enum SceneAction {
-    Win(PuzzleSize, usize, bool), //puzzle size, game idx, grid enabled
+    Win(PuzzleSize, PuzzleId, Box<Puzzle>, bool, bool, Option<u32>), //puzzle size, puzzle, its clues and details, grid enabled, no mistakes, seconds taken (None if resumed)
+    Fail(PuzzleSize, PuzzleId),                                      //puzzle size, puzzle
     MainMenu,
     PuzzleMenu(PuzzleSize),
-    Game(PuzzleSize, usize), //puzzle size, game idx
-    RestoreGame(PuzzleSize, usize, bool, Vec<Vec<Guess>>), //puzzle size, game idx, grid enabled, game data
-    PauseMenu(PuzzleSize, usize, bool, Vec<Vec<Guess>>), //puzzle size, game idx, grid enabled, game data
-    Confirm(Box<SceneAction>, Box<SceneAction>), //action to send if positive, action to send if negative
+    PuzzleOrder(PuzzleSize, PuzzleOrder),
+    EndlessMenu(PuzzleSize),
+    Game(PuzzleSize, PuzzleId),        //puzzle size, puzzle
+    RestartGame(PuzzleSize, PuzzleId), //puzzle size, puzzle
+    PauseMenu(PuzzleSize, PuzzleId, Vec<Vec<Guess>>, u8), //puzzle size, puzzle, board to save, lives lost
+    Confirm(Box<SceneAction>), //action to send if positive, closes if negative
+    Close(SceneResult),        //close the top scene, resuming the one under it
     Settings,
-    SettingsClose(bool, bool, HelpLevel), //music enabled, sfx enabled, help level
+    Statistics,
+    SettingsClose(bool, bool, HelpLevel), //check placement, lives mode, help level
     DeleteSave,
+    SaveError(SaveFailure),
+    SaveRecovery(SaveRecovery),
+}
+
+impl SceneAction {
+    /// Effect shown while swapping to the scene this opens, None if it runs straight away
+    ///
+    /// Reordering the puzzle menu keeps the same scene on screen
+    fn transition(&self) -> Option<Transition> {
+        match self {
+            SceneAction::PuzzleOrder(..) => None,
+            SceneAction::PauseMenu(..)
+            | SceneAction::Confirm(_)
+            | SceneAction::Close(_)
+            | SceneAction::SaveError(_) => Some(Transition::QUICK_FADE),
+            SceneAction::Game(..) | SceneAction::RestartGame(..) => Some(Transition::MOSAIC),
+            _ => Some(Transition::FADE),
+        }
+    }
+
+    /// Menu the puzzle was picked from
+    fn puzzle_menu(size: PuzzleSize, id: PuzzleId) -> SceneAction {
+        match id {
+            PuzzleId::Set(_) => SceneAction::PuzzleMenu(size),
+            PuzzleId::Endless(_) => SceneAction::EndlessMenu(size),
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
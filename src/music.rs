@@ -0,0 +1,270 @@
+use agb::fixnum::Num;
+use agb::sound::mixer::{ChannelId, Mixer, SoundChannel};
+use agb_tracker::{Track, TrackerInner};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+/// Plays a module through agb_tracker at the music volume
+///
+/// agb_tracker sets the volume of its channels itself every frame, so it plays through
+/// [MusicMixer] which scales them on the way to the mixer
+pub struct MusicPlayer {
+    tracker: TrackerInner<'static, MusicChannelId>,
+    channels: MusicChannels,
+}
+
+impl MusicPlayer {
+    pub fn new(track: &'static Track) -> Self {
+        Self {
+            tracker: TrackerInner::new(track),
+            channels: MusicChannels::default(),
+        }
+    }
+
+    /// Advances the module a frame, `volume` multiplies the volume of every note
+    pub fn step(&mut self, mixer: &mut Mixer, volume: Num<i16, 8>) {
+        let mut mixer = MusicMixer::new(mixer, &mut self.channels, volume);
+        self.tracker.step(&mut mixer);
+    }
+
+    pub fn stop(&mut self, mixer: &mut Mixer) {
+        let mut mixer = MusicMixer::new(mixer, &mut self.channels, Num::new(0));
+        self.tracker.stop(&mut mixer);
+    }
+}
+
+/// Mixer channels the tracker is playing, indexed by [MusicChannelId::slot]
+///
+/// agb's ChannelId can't be copied so the tracker gets a slot instead, the generation
+/// changes when a slot is reused so ids the tracker kept for finished notes stop working
+#[derive(Default)]
+struct MusicChannels(Vec<(u32, Option<ChannelId>)>);
+
+struct MusicChannelId {
+    slot: usize,
+    generation: u32,
+}
+
+/// Applies agb_tracker's changes to the mixer with the volume scaled
+///
+/// Changes to a playing channel are collected in a [MusicChannel] and applied once the
+/// tracker moves on to another channel or this is dropped
+struct MusicMixer<'m, 'gba> {
+    mixer: &'m mut Mixer<'gba>,
+    channels: &'m mut MusicChannels,
+    volume: Num<i16, 8>,
+    pending: Option<(usize, MusicChannel)>,
+}
+
+impl<'m, 'gba> MusicMixer<'m, 'gba> {
+    fn new(
+        mixer: &'m mut Mixer<'gba>,
+        channels: &'m mut MusicChannels,
+        volume: Num<i16, 8>,
+    ) -> Self {
+        Self {
+            mixer,
+            channels,
+            volume,
+            pending: None,
+        }
+    }
+
+    fn mixer_channel(&mut self, slot: usize) -> Option<&mut SoundChannel> {
+        let (_, id) = self.channels.0.get(slot)?;
+        self.mixer.channel(id.as_ref()?)
+    }
+
+    fn apply_pending(&mut self) {
+        if let Some((slot, changes)) = self.pending.take() {
+            let volume = self.volume;
+            if let Some(channel) = self.mixer_channel(slot) {
+                changes.apply(channel, volume);
+            }
+        }
+    }
+}
+
+impl Drop for MusicMixer<'_, '_> {
+    fn drop(&mut self) {
+        self.apply_pending();
+    }
+}
+
+impl agb_tracker::Mixer for MusicMixer<'_, '_> {
+    type ChannelId = MusicChannelId;
+    type SoundChannel = MusicChannel;
+
+    fn channel(&mut self, channel_id: &MusicChannelId) -> Option<&mut MusicChannel> {
+        self.apply_pending();
+        let (generation, _) = self.channels.0.get(channel_id.slot)?;
+        if *generation != channel_id.generation {
+            return None;
+        }
+        self.mixer_channel(channel_id.slot)?;
+        let (_, changes) = self
+            .pending
+            .insert((channel_id.slot, MusicChannel::default()));
+        Some(changes)
+    }
+
+    fn play_sound(&mut self, mut channel: MusicChannel) -> Option<MusicChannelId> {
+        self.apply_pending();
+        let mut sound = channel.sound.take()?;
+        channel.apply(&mut sound, self.volume);
+        let id = self.mixer.play_sound(sound)?;
+
+        let mixer = &mut *self.mixer;
+        let free = self.channels.0.iter().position(|(_, playing)| {
+            playing
+                .as_ref()
+                .is_none_or(|id| mixer.channel(id).is_none())
+        });
+        let slot = match free {
+            Some(slot) => slot,
+            None => {
+                self.channels.0.push((0, None));
+                self.channels.0.len() - 1
+            }
+        };
+        let (generation, playing) = &mut self.channels.0[slot];
+        *generation = generation.wrapping_add(1);
+        *playing = Some(id);
+        Some(MusicChannelId {
+            slot,
+            generation: *generation,
+        })
+    }
+}
+
+/// A new note from agb_tracker, or the changes it made to a playing one
+#[derive(Default)]
+struct MusicChannel {
+    /// Only set for new notes
+    sound: Option<SoundChannel>,
+    stopped: bool,
+    paused: Option<bool>,
+    should_loop: bool,
+    volume: Option<Num<i16, 8>>,
+    restart_point: Option<Num<u32, 8>>,
+    playback: Option<Num<u32, 8>>,
+    panning: Option<Num<i16, 8>>,
+    pos: Option<Num<u32, 8>>,
+}
+
+impl MusicChannel {
+    fn apply(&self, channel: &mut SoundChannel, volume: Num<i16, 8>) {
+        if self.stopped {
+            channel.stop();
+            return;
+        }
+        if self.should_loop {
+            channel.should_loop();
+        }
+        if let Some(value) = self.volume {
+            channel.volume(value * volume);
+        }
+        if let Some(value) = self.restart_point {
+            channel.restart_point(value);
+        }
+        if let Some(value) = self.playback {
+            channel.playback(value);
+        }
+        if let Some(value) = self.panning {
+            channel.panning(value);
+        }
+        if let Some(value) = self.pos {
+            channel.set_pos(value);
+        }
+        match self.paused {
+            Some(true) => {
+                channel.pause();
+            }
+            Some(false) => {
+                channel.resume();
+            }
+            None => {}
+        }
+    }
+}
+
+impl agb_tracker::SoundChannel for MusicChannel {
+    fn new(data: &Cow<'static, [u8]>) -> Self {
+        Self {
+            sound: Some(<SoundChannel as agb_tracker::SoundChannel>::new(data)),
+            ..Default::default()
+        }
+    }
+
+    fn stop(&mut self) {
+        self.stopped = true;
+    }
+
+    fn pause(&mut self) -> &mut Self {
+        self.paused = Some(true);
+        self
+    }
+
+    fn resume(&mut self) -> &mut Self {
+        self.paused = Some(false);
+        self
+    }
+
+    fn should_loop(&mut self) -> &mut Self {
+        self.should_loop = true;
+        self
+    }
+
+    fn volume(&mut self, value: impl Into<Num<i16, 8>>) -> &mut Self {
+        self.volume = Some(value.into());
+        self
+    }
+
+    fn restart_point(&mut self, value: impl Into<Num<u32, 8>>) -> &mut Self {
+        self.restart_point = Some(value.into());
+        self
+    }
+
+    fn playback(&mut self, playback_speed: impl Into<Num<u32, 8>>) -> &mut Self {
+        self.playback = Some(playback_speed.into());
+        self
+    }
+
+    fn panning(&mut self, panning: impl Into<Num<i16, 8>>) -> &mut Self {
+        self.panning = Some(panning.into());
+        self
+    }
+
+    fn set_pos(&mut self, pos: impl Into<Num<u32, 8>>) -> &mut Self {
+        self.pos = Some(pos.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MUSIC_MENU;
+    use crate::sfx::MIXER_FREQUENCY;
+
+    fn playing(music: &MusicPlayer, mixer: &mut Mixer) -> usize {
+        music
+            .channels
+            .0
+            .iter()
+            .filter(|(_, id)| id.as_ref().is_some_and(|id| mixer.channel(id).is_some()))
+            .count()
+    }
+
+    #[test_case]
+    fn stopping_frees_every_channel(gba: &mut agb::Gba) {
+        let mut mixer = gba.mixer.mixer(MIXER_FREQUENCY);
+        let mut music = MusicPlayer::new(&MUSIC_MENU);
+
+        music.step(&mut mixer, Num::new(1) / 2);
+        assert!(playing(&music, &mut mixer) > 0);
+
+        music.stop(&mut mixer);
+        assert_eq!(playing(&music, &mut mixer), 0);
+    }
+}
@@ -1,24 +0,0 @@
-use crate::nonos::calc::{Clues, compute_all_clues, parse_grids};
-use crate::puzzle_size::{MAX_COLS, MAX_ROWS, Puzzle};
-use crate::rect_nono_images;
-use agb::display::object::Tag;
-
-const N: usize = 12;
-const W: usize = 20;
-const H: usize = 10;
-
-pub const IMAGES: &Tag = &rect_nono_images::_20X10;
-
-const GAMES: [[[u8; MAX_COLS]; MAX_ROWS]; N] =
-    parse_grids::<N>(include_bytes!("../../assets/20x10.nonos"), W, H);
-
-const CLUES: [Clues; N] = compute_all_clues(GAMES, W, H);
-
-pub fn game(idx: usize) -> Puzzle {
-    Puzzle {
-        row_clues: &CLUES[idx].rows,
-        col_clues: &CLUES[idx].cols,
-        width: W,
-        height: H,
-    }
-}
@@ -1,59 +1,269 @@
 use crate::puzzle_size::{CLUES_PER_COL, CLUES_PER_ROW, MAX_COLS, MAX_ROWS};
 
+/// Optional `key: value` lines before a puzzle's rows
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Meta {
+    pub title: Option<&'static str>,
+    pub author: Option<&'static str>,
+    /// 1 (easiest) to 5
+    pub difficulty: Option<u8>,
+    /// Only show the title once the puzzle has been solved
+    pub hidden: bool,
+}
+
+impl Meta {
+    const NONE: Meta = Meta {
+        title: None,
+        author: None,
+        difficulty: None,
+        hidden: false,
+    };
+}
+
+pub struct ParsedGrids<const N: usize> {
+    pub grids: [[[u8; MAX_COLS]; MAX_ROWS]; N],
+    pub meta: [Meta; N],
+}
+
+/// Parses `N` puzzles of `width` x `height`, one row per line with a blank line between puzzles
+///
+/// Each puzzle can start with header lines, `title`, `author`, `difficulty` (1 to 5) or
+/// `hidden` (yes or no), written as `key: value`
+///
+/// Panics (failing the build when used in a const) with the puzzle index and row, or the
+/// line for a bad header, if the file doesn't match that layout exactly
 pub const fn parse_grids<const N: usize>(
-    bytes: &[u8],
+    bytes: &'static [u8],
     width: usize,
     height: usize,
-) -> [[[u8; MAX_COLS]; MAX_ROWS]; N] {
+) -> ParsedGrids<N> {
     let mut grids = [[[0u8; MAX_COLS]; MAX_ROWS]; N];
+    let mut meta = [Meta::NONE; N];
 
+    let mut i = 0;
     let mut puzzle = 0;
-    let mut y = 0;
-    let mut x = 0;
+    while puzzle < N {
+        while i < bytes.len() && !matches!(bytes[i], b'#' | b'_' | b'\r' | b'\n') {
+            i = parse_header(bytes, i, puzzle, &mut meta[puzzle]);
+        }
+
+        let mut y = 0;
+        while y < height {
+            let mut x = 0;
+            while x < width {
+                if i >= bytes.len() {
+                    fail(puzzle, y, "file ends part way through the row");
+                }
+                grids[puzzle][y][x] = match bytes[i] {
+                    b'#' => 1,
+                    b'_' => 0,
+                    b'\r' | b'\n' => fail(puzzle, y, "row is too short"),
+                    _ => fail(puzzle, y, "unexpected character, only # and _ are allowed"),
+                };
+                i += 1;
+                x += 1;
+            }
+
+            if i < bytes.len() && (bytes[i] == b'#' || bytes[i] == b'_') {
+                fail(puzzle, y, "row is too long");
+            }
+            let last_row = puzzle == N - 1 && y == height - 1;
+            match newline(bytes, i) {
+                Some(next) => i = next,
+                None if i >= bytes.len() && last_row => {}
+                None if i >= bytes.len() => fail(puzzle, y, "file ends after this row"),
+                None => fail(puzzle, y, "unexpected character, only # and _ are allowed"),
+            }
+            y += 1;
+        }
+
+        if puzzle < N - 1 {
+            match newline(bytes, i) {
+                Some(next) => i = next,
+                None if i >= bytes.len() => fail(puzzle + 1, 0, "puzzle is missing"),
+                None => fail(puzzle, height - 1, "expected a blank line after this row"),
+            }
+        }
+        puzzle += 1;
+    }
 
-    let mut i = 0;
     while i < bytes.len() {
-        let b = bytes[i];
+        match newline(bytes, i) {
+            Some(next) => i = next,
+            None => fail(N, 0, "more puzzles than expected"),
+        }
+    }
 
-        let val = if b == b'#' {
-            1u8
-        } else if b == b'_' {
-            0u8
-        } else {
-            i += 1;
-            continue;
-        };
+    ParsedGrids { grids, meta }
+}
+
+/// Reads the header line starting at `i` into `meta`, returning the index of the next line
+const fn parse_header(bytes: &'static [u8], i: usize, puzzle: usize, meta: &mut Meta) -> usize {
+    let mut end = i;
+    let mut colon = None;
+    while end < bytes.len() && bytes[end] != b'\r' && bytes[end] != b'\n' {
+        if colon.is_none() && bytes[end] == b':' {
+            colon = Some(end);
+        }
+        end += 1;
+    }
+    let Some(colon) = colon else {
+        fail_line(bytes, i, puzzle, "expected a row or a `key: value` header");
+    };
 
-        grids[puzzle][y][x] = val;
+    let mut start = colon + 1;
+    while start < end && bytes[start] == b' ' {
+        start += 1;
+    }
+    let mut value_end = end;
+    while value_end > start && bytes[value_end - 1] == b' ' {
+        value_end -= 1;
+    }
+    if start == value_end {
+        fail_line(bytes, i, puzzle, "header has no value");
+    }
+    let value = sub(bytes, start, value_end);
 
-        x += 1;
-        if x == width {
-            x = 0;
-            y += 1;
-            if y == height {
-                y = 0;
-                puzzle += 1;
-                if puzzle == N {
-                    break;
-                }
+    match sub(bytes, i, colon) {
+        b"title" if meta.title.is_none() => meta.title = Some(utf8(bytes, i, value, puzzle)),
+        b"author" if meta.author.is_none() => meta.author = Some(utf8(bytes, i, value, puzzle)),
+        b"difficulty" if meta.difficulty.is_none() => {
+            meta.difficulty = match value {
+                [digit @ b'1'..=b'5'] => Some(*digit - b'0'),
+                _ => fail_line(bytes, i, puzzle, "difficulty must be 1 to 5"),
             }
         }
+        b"hidden" => {
+            meta.hidden = match value {
+                b"yes" => true,
+                b"no" => false,
+                _ => fail_line(bytes, i, puzzle, "hidden must be yes or no"),
+            }
+        }
+        b"title" | b"author" | b"difficulty" => fail_line(bytes, i, puzzle, "header is repeated"),
+        _ => fail_line(
+            bytes,
+            i,
+            puzzle,
+            "unknown header, expected title, author, difficulty or hidden",
+        ),
+    }
+
+    match newline(bytes, end) {
+        Some(next) => next,
+        None if end >= bytes.len() => fail_line(bytes, i, puzzle, "file ends after a header"),
+        None => fail_line(bytes, i, puzzle, "unexpected character in header"),
+    }
+}
+
+/// `bytes[start..end]`, kept `'static` so header values can be stored
+const fn sub(bytes: &'static [u8], start: usize, end: usize) -> &'static [u8] {
+    bytes.split_at(end).0.split_at(start).1
+}
+
+/// `value` as a str, `line_start` is where its header starts in `bytes`
+const fn utf8(
+    bytes: &[u8],
+    line_start: usize,
+    value: &'static [u8],
+    puzzle: usize,
+) -> &'static str {
+    match core::str::from_utf8(value) {
+        Ok(text) => text,
+        Err(_) => fail_line(bytes, line_start, puzzle, "header value isn't valid UTF-8"),
+    }
+}
+
+/// Index after the line ending at `i`, if there is one
+const fn newline(bytes: &[u8], i: usize) -> Option<usize> {
+    if i < bytes.len() && bytes[i] == b'\n' {
+        Some(i + 1)
+    } else if i + 1 < bytes.len() && bytes[i] == b'\r' && bytes[i + 1] == b'\n' {
+        Some(i + 2)
+    } else {
+        None
+    }
+}
+
+/// Panics with "invalid nonos, puzzle {puzzle} row {row}: {reason}"
+const fn fail(puzzle: usize, row: usize, reason: &str) -> ! {
+    report(puzzle, " row ", row, reason)
+}
+
+/// Panics with "invalid nonos, puzzle {puzzle} line {line}: {reason}", `i` is the start of
+/// the line in `bytes` and lines are counted from 1 like a text editor
+const fn fail_line(bytes: &[u8], i: usize, puzzle: usize, reason: &str) -> ! {
+    let mut line = 1;
+    let mut j = 0;
+    while j < i {
+        if bytes[j] == b'\n' {
+            line += 1;
+        }
+        j += 1;
+    }
+    report(puzzle, " line ", line, reason)
+}
+
+const fn report(puzzle: usize, place: &str, number: usize, reason: &str) -> ! {
+    let mut msg = [0u8; 128];
+    let mut len = 0;
+    len = push_str(&mut msg, len, "invalid nonos, puzzle ");
+    len = push_usize(&mut msg, len, puzzle);
+    len = push_str(&mut msg, len, place);
+    len = push_usize(&mut msg, len, number);
+    len = push_str(&mut msg, len, ": ");
+    len = push_str(&mut msg, len, reason);
 
+    let (msg, _) = msg.split_at(len);
+    match core::str::from_utf8(msg) {
+        Ok(msg) => panic!("{}", msg),
+        Err(_) => panic!("invalid nonos"),
+    }
+}
+
+const fn push_str(buf: &mut [u8], mut len: usize, text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() && len < buf.len() {
+        buf[len] = bytes[i];
+        len += 1;
         i += 1;
     }
+    len
+}
 
-    grids
+const fn push_usize(buf: &mut [u8], mut len: usize, value: usize) -> usize {
+    let mut digits = [0u8; 20];
+    let mut count = 0;
+    let mut value = value;
+    loop {
+        digits[count] = b'0' + (value % 10) as u8;
+        count += 1;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    while count > 0 && len < buf.len() {
+        count -= 1;
+        buf[len] = digits[count];
+        len += 1;
+    }
+    len
 }
 
 #[derive(Copy, Clone)]
 pub struct Clues {
     pub rows: [[u8; CLUES_PER_ROW]; MAX_ROWS],
     pub cols: [[u8; CLUES_PER_COL]; MAX_COLS],
+    /// The drawn picture, a mask per row (bit x is column x)
+    pub solution: [u32; MAX_ROWS],
 }
 
 pub const fn compute_clues(grid: [[u8; MAX_COLS]; MAX_ROWS], width: usize, height: usize) -> Clues {
     let mut rows = [[0u8; CLUES_PER_ROW]; MAX_ROWS];
     let mut cols = [[0u8; CLUES_PER_COL]; MAX_COLS];
+    let mut solution = [0u32; MAX_ROWS];
 
     let mut y = 0;
     while y < height {
@@ -62,6 +272,7 @@ pub const fn compute_clues(grid: [[u8; MAX_COLS]; MAX_ROWS], width: usize, heigh
         let mut out = 0;
         while x < width {
             if grid[y][x] != 0 {
+                solution[y] |= 1 << x;
                 run += 1;
             } else if run != 0 {
                 rows[y][out] = run;
@@ -97,7 +308,11 @@ pub const fn compute_clues(grid: [[u8; MAX_COLS]; MAX_ROWS], width: usize, heigh
         x += 1;
     }
 
-    Clues { rows, cols }
+    Clues {
+        rows,
+        cols,
+        solution,
+    }
 }
 
 pub const fn compute_all_clues<const N: usize>(
@@ -108,6 +323,7 @@ pub const fn compute_all_clues<const N: usize>(
     let mut out = [Clues {
         rows: [[0u8; CLUES_PER_ROW]; MAX_ROWS],
         cols: [[0u8; CLUES_PER_COL]; MAX_COLS],
+        solution: [0; MAX_ROWS],
     }; N];
 
     let mut i = 0;
@@ -118,3 +334,58 @@ pub const fn compute_all_clues<const N: usize>(
 
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle_size::PuzzleSize;
+
+    #[test_case]
+    fn headers_are_optional(_gba: &mut agb::Gba) {
+        let parsed = parse_grids::<2>(
+            b"title: Dot\r\nauthor:  Someone \r\ndifficulty: 2\r\nhidden: yes\r\n#_\r\n__\r\n\r\n_#\r\n#_\r\n",
+            2,
+            2,
+        );
+
+        assert_eq!(
+            parsed.meta[0],
+            Meta {
+                title: Some("Dot"),
+                author: Some("Someone"),
+                difficulty: Some(2),
+                hidden: true,
+            }
+        );
+        assert_eq!(parsed.meta[1], Meta::NONE);
+        assert_eq!(parsed.grids[0][0][..2], [1, 0]);
+        assert_eq!(parsed.grids[1][1][..2], [1, 0]);
+    }
+
+    #[test_case]
+    fn puzzles_have_titles(_gba: &mut agb::Gba) {
+        let puzzle = PuzzleSize::_8x8.games(9);
+
+        assert_eq!(puzzle.title, Some("Potion"));
+        assert!(puzzle.hidden);
+        assert_eq!(puzzle.author, None);
+        assert_eq!(PuzzleSize::_6x6.games(6).title, Some("Notes"));
+    }
+
+    #[test_case]
+    fn puzzles_are_rated(_gba: &mut agb::Gba) {
+        for size in PuzzleSize::all() {
+            for idx in 0..size.game_count() {
+                assert!((1..=5).contains(&size.games(idx).difficulty));
+            }
+        }
+        // line solvable in 2 passes against one that needs guessing
+        assert!(PuzzleSize::_8x8.games(6).difficulty > PuzzleSize::_8x8.games(0).difficulty);
+        // size alone doesn't rate the largest puzzles 5
+        assert!(PuzzleSize::_22x12.games(3).difficulty < PuzzleSize::_22x12.games(1).difficulty);
+        assert!(
+            (0..PuzzleSize::_25x25.game_count())
+                .any(|idx| PuzzleSize::_25x25.games(idx).difficulty < 5)
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use crate::nonos::calc::compute_clues;
+use crate::nonos::rating::difficulty;
+use crate::nonos::solver::Solver;
+use crate::puzzle_size::{CLUES_PER_COL, CLUES_PER_ROW, MAX_COLS, MAX_ROWS, Puzzle, PuzzleSize};
+
+/// Seeds are shown as 5 digits so they're easy to share
+pub const SEED_DIGITS: usize = 5;
+pub const MAX_SEED: u32 = 99_999;
+
+/// Chance of each cell being filled, denser grids are far more likely to be line solvable
+const FILL_PERCENT: u32 = 60;
+
+/// Random boards tried at each fill chance, most seeds need one or two
+const MAX_ATTEMPTS: usize = 100;
+
+/// Added to the fill chance each time [MAX_ATTEMPTS] boards fail, up to [MAX_FILL_PERCENT]
+const FILL_STEP: u32 = 5;
+const MAX_FILL_PERCENT: u32 = 85;
+
+/// Builds a random puzzle for `size` that line solving alone finishes, so it has exactly
+/// one solution and never needs guessing
+///
+/// The same size and seed always give the same puzzle. A seed that doesn't find one within
+/// [MAX_ATTEMPTS] keeps drawing boards from where it got to, a little denser each time.
+/// Boards that are all filled or all empty are skipped as there's nothing to solve
+pub fn generate(size: PuzzleSize, seed: u32) -> Puzzle {
+    let (width, height) = size.dimensions();
+    let mut rng = Xorshift::new(seed, width, height);
+    let mut fill_percent = FILL_PERCENT;
+
+    loop {
+        for _ in 0..MAX_ATTEMPTS {
+            let mut grid = [[0u8; MAX_COLS]; MAX_ROWS];
+            for row in grid.iter_mut().take(height) {
+                for cell in row.iter_mut().take(width) {
+                    *cell = (rng.next() % 100 < fill_percent) as u8;
+                }
+            }
+            if is_trivial(&grid, width, height) || !clues_fit(&grid, width, height) {
+                continue;
+            }
+            if let Some(puzzle) = line_solvable(grid, width, height) {
+                return puzzle;
+            }
+        }
+        fill_percent = (fill_percent + FILL_STEP).min(MAX_FILL_PERCENT);
+    }
+}
+
+/// The puzzle for `grid`, None if line solving alone doesn't finish it
+fn line_solvable(grid: [[u8; MAX_COLS]; MAX_ROWS], width: usize, height: usize) -> Option<Puzzle> {
+    let clues = compute_clues(grid, width, height);
+    let mut puzzle = Puzzle {
+        row_clues: clues.rows,
+        col_clues: clues.cols,
+        width,
+        height,
+        solution: clues.solution,
+        unique: true,
+        title: None,
+        author: None,
+        difficulty: 1,
+        hidden: false,
+    };
+
+    let mut solver = Solver::new(&puzzle);
+    if let Ok(passes) = solver.solve()
+        && solver.is_solved()
+    {
+        // line solving finishes it, so nothing is guessed or left undetermined
+        puzzle.difficulty = difficulty(passes, 0, 0, width * height);
+        Some(puzzle)
+    } else {
+        None
+    }
+}
+
+/// True if every cell is the same, leaving nothing to solve
+fn is_trivial(grid: &[[u8; MAX_COLS]; MAX_ROWS], width: usize, height: usize) -> bool {
+    let filled = grid
+        .iter()
+        .take(height)
+        .map(|row| row[..width].iter().filter(|&&cell| cell != 0).count())
+        .sum::<usize>();
+    filled == 0 || filled == width * height
+}
+
+/// True if no row or column has more runs than the board has space to show
+fn clues_fit(grid: &[[u8; MAX_COLS]; MAX_ROWS], width: usize, height: usize) -> bool {
+    let runs = |line: &mut dyn Iterator<Item = u8>| {
+        let mut runs = 0;
+        let mut previous = 0;
+        for cell in line {
+            if cell != 0 && previous == 0 {
+                runs += 1;
+            }
+            previous = cell;
+        }
+        runs
+    };
+
+    (0..height).all(|y| runs(&mut grid[y].iter().take(width).copied()) <= CLUES_PER_ROW)
+        && (0..width)
+            .all(|x| runs(&mut grid.iter().take(height).map(|row| row[x])) <= CLUES_PER_COL)
+}
+
+/// Small and fast, and unlike the agb generator it will never change between versions,
+/// so a seed keeps giving the same puzzle
+struct Xorshift(u32);
+
+impl Xorshift {
+    fn new(seed: u32, width: usize, height: usize) -> Self {
+        let state = seed.wrapping_mul(0x9E37_79B9) ^ ((width << 8 | height) as u32);
+        let mut rng = Xorshift(state.max(1));
+        // early values follow the seed closely
+        for _ in 0..8 {
+            rng.next();
+        }
+        rng
+    }
+
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nonos::solver::Cell;
+
+    #[test_case]
+    fn seed_gives_same_puzzle(_gba: &mut agb::Gba) {
+        let first = generate(PuzzleSize::_10x10, 1234);
+        let second = generate(PuzzleSize::_10x10, 1234);
+        let other = generate(PuzzleSize::_10x10, 1235);
+
+        assert_eq!(first.solution, second.solution);
+        assert_eq!(first.row_clues, second.row_clues);
+        assert_ne!(first.solution, other.solution);
+    }
+
+    /// Asserts the puzzle isn't a full board and line solving finds its solution
+    fn assert_line_solvable(size: PuzzleSize, seed: u32) {
+        let (width, height) = size.dimensions();
+        let puzzle = generate(size, seed);
+        let filled = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| puzzle.is_filled(x, y))
+            .count();
+        assert!(
+            filled < width * height,
+            "{width}x{height} seed {seed} is full"
+        );
+
+        let mut solver = Solver::new(&puzzle);
+        solver.solve().unwrap();
+
+        assert!(solver.is_solved());
+        for (y, row) in solver.cells().iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                assert_eq!(cell == Cell::Filled, puzzle.is_filled(x, y));
+            }
+        }
+    }
+
+    #[test_case]
+    fn generated_puzzles_are_line_solvable(_gba: &mut agb::Gba) {
+        for size in PuzzleSize::all() {
+            for seed in (0..MAX_SEED).step_by(4_999).chain([MAX_SEED]) {
+                assert_line_solvable(size, seed);
+            }
+        }
+    }
+
+    #[test_case]
+    fn seeds_failing_at_the_first_fill_get_denser_boards(_gba: &mut agb::Gba) {
+        // these found nothing in their first MAX_ATTEMPTS boards
+        assert_line_solvable(PuzzleSize::_6x6, 13_624);
+        for seed in [599, 842, 1_120] {
+            assert_line_solvable(PuzzleSize::_25x25, seed);
+        }
+    }
+}
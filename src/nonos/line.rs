@@ -0,0 +1,136 @@
+// Single line solving, kept free of crate dependencies so the build script can use it too
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Cell {
+    Unknown,
+    Filled,
+    Empty,
+}
+
+/// The clues can't be satisfied with the cells already decided
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Contradiction;
+
+/// Decides every cell in `line` that is the same in all arrangements of `clues`
+///
+/// Compares the left-most and right-most arrangements: a cell covered by the same
+/// block in both (overlap, or pushed from an edge by decided cells) must be filled
+/// and a cell in the same gap in both must be empty
+///
+/// Returns true if any cell was decided
+pub fn solve_line(clues: &[u8], line: &mut [Cell]) -> Result<bool, Contradiction> {
+    let blocks: Vec<usize> = clues
+        .iter()
+        .take_while(|&&clue| clue != 0)
+        .map(|&clue| clue as usize)
+        .collect();
+
+    let left = leftmost(&blocks, line).ok_or(Contradiction)?;
+
+    let reversed_blocks: Vec<usize> = blocks.iter().rev().copied().collect();
+    let reversed_line: Vec<Cell> = line.iter().rev().copied().collect();
+    let right: Vec<usize> = leftmost(&reversed_blocks, &reversed_line)
+        .ok_or(Contradiction)?
+        .iter()
+        .zip(&reversed_blocks)
+        .rev()
+        .map(|(&start, &len)| line.len() - start - len)
+        .collect();
+
+    let left = labels(&blocks, &left, line.len());
+    let right = labels(&blocks, &right, line.len());
+
+    let mut changed = false;
+    for (cell, (l, r)) in line.iter_mut().zip(left.iter().zip(&right)) {
+        if *cell == Cell::Unknown && l == r {
+            *cell = if l % 2 == 1 {
+                Cell::Filled
+            } else {
+                Cell::Empty
+            };
+            changed = true;
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Start of each block when every block is as far left as the decided cells allow
+fn leftmost(blocks: &[usize], line: &[Cell]) -> Option<Vec<usize>> {
+    let mut starts = vec![0; blocks.len()];
+    if place(blocks, line, 0, 0, &mut starts) {
+        Some(starts)
+    } else {
+        None
+    }
+}
+
+/// Places `blocks[block..]` starting no earlier than `from`
+fn place(blocks: &[usize], line: &[Cell], block: usize, from: usize, starts: &mut [usize]) -> bool {
+    let Some(&len) = blocks.get(block) else {
+        return line[from.min(line.len())..]
+            .iter()
+            .all(|&cell| cell != Cell::Filled);
+    };
+
+    let mut start = from;
+    while start + len <= line.len() {
+        let fits = line[start..start + len]
+            .iter()
+            .all(|&cell| cell != Cell::Empty)
+            && line.get(start + len) != Some(&Cell::Filled);
+        if fits {
+            starts[block] = start;
+            if place(blocks, line, block + 1, start + len + 1, starts) {
+                return true;
+            }
+        }
+        // a filled cell can't be left behind uncovered
+        if line[start] == Cell::Filled {
+            return false;
+        }
+        start += 1;
+    }
+
+    false
+}
+
+/// Labels each cell with the gap (even) or block (odd) covering it
+fn labels(blocks: &[usize], starts: &[usize], len: usize) -> Vec<usize> {
+    let mut output = vec![blocks.len() * 2; len];
+    let mut pos = 0;
+    for (i, (&start, &block)) in starts.iter().zip(blocks).enumerate() {
+        output[pos..start].fill(i * 2);
+        output[start..start + block].fill(i * 2 + 1);
+        pos = start + block;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_case]
+    fn overlap_and_edges(_gba: &mut agb::Gba) {
+        use Cell::{Empty as E, Filled as F, Unknown as U};
+
+        let mut line = [U; 5];
+        assert_eq!(solve_line(&[4, 0], &mut line), Ok(true));
+        assert_eq!(line, [U, F, F, F, U]);
+
+        let mut line = [F, U, U, U, U];
+        assert_eq!(solve_line(&[2, 0], &mut line), Ok(true));
+        assert_eq!(line, [F, F, E, E, E]);
+
+        let mut line = [U, U, E, U, U, U];
+        assert_eq!(solve_line(&[3, 0], &mut line), Ok(true));
+        assert_eq!(line, [E, E, E, F, F, F]);
+
+        let mut line = [F, F, F];
+        assert_eq!(solve_line(&[1, 1, 0], &mut line), Err(Contradiction));
+    }
+}
@@ -1,9 +1,8 @@
-pub mod _10x10;
-pub mod _12x12;
-pub mod _20x10;
-pub mod _22x12;
-pub mod _6x6;
-pub mod _8x8;
 mod calc;
+pub mod generator;
+mod line;
+mod rating;
+pub mod solver;
 
-pub const TOTAL_GAME_COUNT: usize = 108;
+// a module for each file in assets, generated by the build script
+include!(concat!(env!("OUT_DIR"), "/nonos.rs"));
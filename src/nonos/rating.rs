@@ -0,0 +1,37 @@
+// Puzzle difficulty, kept free of crate dependencies so the build script can use it too
+
+/// Rates a puzzle from 1 (easiest) to 5 by how much work the solver needed
+///
+/// Every round of line solving, guess and tenth of the board left after line solving adds
+/// to the effort. The board size doesn't, so each size keeps its own spread of ratings
+/// rather than every large puzzle being rated 5
+pub fn difficulty(passes: usize, guesses: usize, undetermined: usize, area: usize) -> u8 {
+    let effort = passes + guesses.min(10) * 2 + undetermined * 10 / area.max(1);
+    match effort {
+        0..=2 => 1,
+        3..=4 => 2,
+        5..=7 => 3,
+        8..=12 => 4,
+        _ => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_case]
+    fn size_alone_doesnt_rate(_gba: &mut agb::Gba) {
+        assert_eq!(difficulty(2, 0, 0, 6 * 6), 1);
+        assert_eq!(difficulty(2, 0, 0, 25 * 25), 1);
+    }
+
+    #[test_case]
+    fn guessing_rates_higher(_gba: &mut agb::Gba) {
+        let line_solvable = difficulty(3, 0, 0, 10 * 10);
+        let needs_guessing = difficulty(3, 2, 30, 10 * 10);
+
+        assert!(needs_guessing > line_solvable);
+        assert_eq!(difficulty(3, 100, 100, 10 * 10), 5);
+    }
+}
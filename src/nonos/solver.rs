@@ -0,0 +1,273 @@
+use crate::nonos::line::solve_line;
+pub use crate::nonos::line::{Cell, Contradiction};
+use crate::puzzle_size::Puzzle;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Solves a puzzle one row or column at a time, only deciding cells that
+/// every arrangement of that line's clues agree on
+pub struct Solver<'a> {
+    puzzle: &'a Puzzle,
+    cells: Vec<Vec<Cell>>,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(puzzle: &'a Puzzle) -> Self {
+        Self {
+            puzzle,
+            cells: vec![vec![Cell::Unknown; puzzle.width]; puzzle.height],
+        }
+    }
+
+    /// Indexed `[y][x]`
+    pub fn cells(&self) -> &[Vec<Cell>] {
+        &self.cells
+    }
+
+    /// Solves every row then every column once
+    ///
+    /// Returns true if any cell was decided
+    pub fn step(&mut self) -> Result<bool, Contradiction> {
+        let mut changed = false;
+        let mut line = Vec::with_capacity(self.puzzle.width.max(self.puzzle.height));
+
+        for y in 0..self.puzzle.height {
+            line.clear();
+            line.extend_from_slice(&self.cells[y]);
+            if solve_line(&self.puzzle.row_clues[y], &mut line)? {
+                self.cells[y].copy_from_slice(&line);
+                changed = true;
+            }
+        }
+
+        for x in 0..self.puzzle.width {
+            line.clear();
+            line.extend(self.cells.iter().map(|row| row[x]));
+            if solve_line(&self.puzzle.col_clues[x], &mut line)? {
+                for (row, &cell) in self.cells.iter_mut().zip(&line) {
+                    row[x] = cell;
+                }
+                changed = true;
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Repeats [Self::step] until nothing more can be decided
+    ///
+    /// Returns the number of steps that decided cells
+    pub fn solve(&mut self) -> Result<usize, Contradiction> {
+        let mut steps = 0;
+        while self.step()? {
+            steps += 1;
+        }
+        Ok(steps)
+    }
+
+    /// True once every cell has been decided
+    pub fn is_solved(&self) -> bool {
+        self.cells
+            .iter()
+            .flatten()
+            .all(|&cell| cell != Cell::Unknown)
+    }
+
+    /// Cells that line solving can't decide, as `(x, y)`
+    #[cfg(test)]
+    pub fn undetermined(&self) -> Vec<(usize, usize)> {
+        let mut output = Vec::new();
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if cell == Cell::Unknown {
+                    output.push((x, y));
+                }
+            }
+        }
+        output
+    }
+}
+
+/// Cells that every solution agrees on, indexed `[y][x]`
+///
+/// This is the drawn picture when it's the only solution, otherwise only what
+/// line solving can decide from the clues
+pub fn answer(puzzle: &Puzzle) -> Vec<Vec<Cell>> {
+    if !puzzle.unique {
+        let mut solver = Solver::new(puzzle);
+        if solver.solve().is_err() {
+            panic!("clues contradict for {puzzle:?}");
+        }
+        return solver.cells;
+    }
+
+    (0..puzzle.height)
+        .map(|y| {
+            (0..puzzle.width)
+                .map(|x| {
+                    if puzzle.is_filled(x, y) {
+                        Cell::Filled
+                    } else {
+                        Cell::Empty
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nonos::{_6x6, _8x8, _10x10, _12x12, _20x10, _22x12, _25x25};
+    use crate::puzzle_size::{MAX_COLS, MAX_ROWS, PuzzleSize};
+
+    /// Puzzles that need more than line solving, with a mask per row of the cells
+    /// left undetermined (bit x is column x)
+    #[rustfmt::skip]
+    const UNDETERMINED: &[(PuzzleSize, usize, &[u32])] = &[
+        (PuzzleSize::_6x6, 3, &[0x1e, 0x0, 0x0, 0x0, 0x1e]),
+        (PuzzleSize::_6x6, 10, &[0x33, 0x33, 0x0, 0x0, 0x33, 0x33]),
+        (PuzzleSize::_6x6, 11, &[0x28, 0x22, 0x22, 0x0, 0x2e, 0x26]),
+        (PuzzleSize::_6x6, 12, &[0x0, 0x33, 0x35, 0x11, 0x11, 0x37]),
+        (PuzzleSize::_8x8, 6, &[0xee, 0x0, 0xee, 0x6c]),
+        (PuzzleSize::_8x8, 9, &[0x3e, 0x0, 0x3f, 0xf, 0xf, 0x5, 0x3]),
+        (PuzzleSize::_8x8, 10, &[0xe0, 0xe0, 0xa0]),
+        (PuzzleSize::_8x8, 20, &[0x60, 0x60]),
+        (PuzzleSize::_10x10, 0, &[0x6c, 0x0, 0x6c]),
+        (PuzzleSize::_10x10, 5, &[0x0, 0x0, 0x0, 0x0, 0x0, 0xa0, 0xa0]),
+        (PuzzleSize::_10x10, 6, &[0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1e0, 0x1e0, 0x1e0]),
+        (PuzzleSize::_10x10, 7, &[0x3f7, 0x3f7, 0x3f7, 0x3df, 0x387, 0x3df, 0x0, 0xf5, 0x2d5, 0x2f5]),
+        (PuzzleSize::_10x10, 8, &[0x50, 0x0, 0x0, 0x0, 0x48, 0x0, 0x2dd, 0x3cf, 0x285, 0x3cf]),
+        (PuzzleSize::_10x10, 9, &[0x0, 0x360, 0x360, 0x360, 0x0, 0x36c, 0x360, 0x36c]),
+        (PuzzleSize::_10x10, 10, &[0x0, 0x3ff, 0x3ff, 0x3ff, 0x303, 0x3ff, 0x3cf, 0x3ff, 0x3ff, 0x3ff]),
+        (PuzzleSize::_10x10, 11, &[0x0, 0x0, 0x1fe, 0x1ee, 0x1ee, 0x1fe, 0x1ec, 0x16c]),
+        (PuzzleSize::_10x10, 17, &[0x0, 0x0, 0x0, 0x0, 0x18, 0x0, 0x18]),
+        (PuzzleSize::_10x10, 20, &[0x1b0, 0x390, 0x0, 0x0, 0x273, 0x131, 0x120, 0x47, 0x37, 0x36]),
+        (PuzzleSize::_12x12, 4, &[0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xd00, 0xd00, 0xd00, 0xd00]),
+        (PuzzleSize::_12x12, 5, &[0xe8, 0x48, 0xe8]),
+        (PuzzleSize::_12x12, 8, &[0xf0f, 0x0, 0xf0f, 0xf08, 0x0, 0xf00, 0x0, 0x0, 0xf00, 0x0, 0x0, 0x808]),
+        (PuzzleSize::_12x12, 10, &[0x0, 0x0, 0x0, 0x198, 0x198, 0x198, 0x198]),
+        (PuzzleSize::_12x12, 11, &[0xc03, 0xc03, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0xc03, 0xc03]),
+        (PuzzleSize::_12x12, 13, &[0x57b, 0x57b, 0x0, 0x0, 0xf7f, 0xf7f, 0xf7f, 0xe37, 0x0, 0xe77, 0xf7f, 0xf7f]),
+        (PuzzleSize::_12x12, 14, &[0x480, 0x0, 0x0, 0x0, 0x0, 0x480]),
+        (PuzzleSize::_12x12, 15, &[0xf9b, 0xf9b, 0x0, 0xffb, 0xffb, 0x198, 0x198, 0xffb, 0xffb, 0x0, 0xf9b, 0xf9b]),
+        (PuzzleSize::_12x12, 17, &[0x3f7, 0xbf7, 0xbf7, 0x3f7, 0x3f7, 0xbf7, 0x3f7, 0xbf7, 0xbf7, 0x3f7, 0x3f7, 0x3f7]),
+        (PuzzleSize::_12x12, 20, &[0x0, 0x0, 0x0, 0x0, 0x0, 0x3cc, 0x3cc, 0x0, 0x0, 0x0, 0x30c, 0x30c]),
+        (PuzzleSize::_20x10, 3, &[0xfffff, 0xffeff, 0xfffff, 0xfffff, 0xfffff, 0xeffff, 0xe7fff, 0xfffff, 0xfffff]),
+        (PuzzleSize::_20x10, 5, &[0xfffff, 0xfffff, 0xffffd, 0xffffd, 0x3fff8, 0x3fff8, 0xffffd, 0xffffd, 0xfffff, 0xfffff]),
+        (PuzzleSize::_20x10, 8, &[0x1c73e, 0x1c73e, 0x73e, 0x1c73e, 0x1c73e, 0x1c73e, 0x1c73e, 0x1c73e, 0x1c73e, 0x1c73e]),
+        (PuzzleSize::_20x10, 9, &[0xfffff, 0xf000f, 0xfffff, 0x0, 0xfc03f, 0x0, 0x3c03f, 0xfc61f, 0x70007, 0x7c61f]),
+        (PuzzleSize::_20x10, 10, &[0x2001, 0x0, 0x2001]),
+        (PuzzleSize::_20x10, 11, &[0xfff, 0xfff, 0xfff, 0xfff, 0x0, 0xfff, 0xf7f, 0x770, 0xf7f, 0xfff]),
+        (PuzzleSize::_22x12, 1, &[0x50, 0x0, 0x50]),
+        (PuzzleSize::_22x12, 2, &[0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3c00, 0x0, 0x0, 0x3c00]),
+        (PuzzleSize::_22x12, 5, &[0x3fffe0, 0x3fffe0, 0x3fffe0, 0x3fffe0, 0x3fffef, 0x3fffef, 0x3fffef, 0x3f807f, 0x3dffff, 0x3c000f, 0x3fffff, 0x3fffff]),
+        (PuzzleSize::_22x12, 6, &[0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x600, 0x600]),
+        (PuzzleSize::_22x12, 7, &[0x0, 0x0, 0x3fffff, 0x3fffff, 0x3fffff, 0x3fe7ff, 0x3ffbff, 0x3ffbff, 0x3fffff, 0x3fffff, 0x3fffff, 0x3fffff]),
+        (PuzzleSize::_22x12, 8, &[0x0, 0x3fffff, 0x3fffff, 0x0, 0x0, 0x3f807f, 0x3fffff, 0x0, 0x3fffff, 0x3fe1ff, 0x3fe1ff, 0x3fe1ff]),
+        (PuzzleSize::_22x12, 9, &[0x1fffff, 0x1fefff, 0x1eeeff, 0x1efeff, 0x1def7f, 0x1c037f, 0x0, 0x1dff7f, 0x1fefff, 0x1fffff, 0x1fffff, 0x1fffff]),
+        (PuzzleSize::_22x12, 10, &[0x1fffff, 0x1fffff, 0x1fffff, 0x1fffff, 0x1fffff, 0x1fffff, 0x1fffff, 0x1fffff, 0x1fffff, 0x1fffff, 0x1fffff, 0x1fffff]),
+        (PuzzleSize::_22x12, 11, &[0x3fffff, 0x3fffff, 0x3fffff, 0x3fffbf, 0x3fffbf, 0x3fffbf, 0x3fffbf, 0x3fffbf, 0x0, 0x3f87f0, 0x3fdff0, 0x3fdfe0]),
+    ];
+
+    fn solution(size: PuzzleSize, idx: usize) -> &'static [[u8; MAX_COLS]; MAX_ROWS] {
+        match size {
+            PuzzleSize::_6x6 => &_6x6::GAMES[idx],
+            PuzzleSize::_8x8 => &_8x8::GAMES[idx],
+            PuzzleSize::_10x10 => &_10x10::GAMES[idx],
+            PuzzleSize::_12x12 => &_12x12::GAMES[idx],
+            PuzzleSize::_20x10 => &_20x10::GAMES[idx],
+            PuzzleSize::_22x12 => &_22x12::GAMES[idx],
+            PuzzleSize::_25x25 => &_25x25::GAMES[idx],
+            _ => panic!("no solutions for {size:?}"),
+        }
+    }
+
+    fn check_size(size: PuzzleSize) {
+        for idx in 0..size.game_count() {
+            let puzzle = size.games(idx);
+            let solution = solution(size, idx);
+            let mut solver = Solver::new(&puzzle);
+            assert!(solver.solve().is_ok(), "{size:?} #{idx} contradicts");
+
+            for (y, row) in solver.cells().iter().enumerate() {
+                for (x, &cell) in row.iter().enumerate() {
+                    let expected = if solution[y][x] == 1 {
+                        Cell::Filled
+                    } else {
+                        Cell::Empty
+                    };
+                    assert_eq!(puzzle.is_filled(x, y), solution[y][x] == 1);
+                    assert!(
+                        cell == Cell::Unknown || cell == expected,
+                        "{size:?} #{idx} wrong at {x},{y}"
+                    );
+                }
+            }
+
+            let mut undetermined = vec![0_u32; puzzle.height];
+            for (x, y) in solver.undetermined() {
+                undetermined[y] |= 1 << x;
+            }
+            let mut expected = vec![0_u32; puzzle.height];
+            if let Some((_, _, rows)) = UNDETERMINED
+                .iter()
+                .find(|(s, i, _)| *s == size && *i == idx)
+            {
+                expected[..rows.len()].copy_from_slice(rows);
+            }
+            assert_eq!(undetermined, expected, "{size:?} #{idx} undetermined cells");
+        }
+    }
+
+    #[test_case]
+    fn solves_6x6(_gba: &mut agb::Gba) {
+        check_size(PuzzleSize::_6x6);
+    }
+
+    #[test_case]
+    fn solves_8x8(_gba: &mut agb::Gba) {
+        check_size(PuzzleSize::_8x8);
+    }
+
+    #[test_case]
+    fn solves_10x10(_gba: &mut agb::Gba) {
+        check_size(PuzzleSize::_10x10);
+    }
+
+    #[test_case]
+    fn solves_12x12(_gba: &mut agb::Gba) {
+        check_size(PuzzleSize::_12x12);
+    }
+
+    #[test_case]
+    fn solves_20x10(_gba: &mut agb::Gba) {
+        check_size(PuzzleSize::_20x10);
+    }
+
+    #[test_case]
+    fn solves_22x12(_gba: &mut agb::Gba) {
+        check_size(PuzzleSize::_22x12);
+    }
+
+    #[test_case]
+    fn solves_25x25(_gba: &mut agb::Gba) {
+        check_size(PuzzleSize::_25x25);
+    }
+
+    #[test_case]
+    fn answer_only_trusts_unique_solutions(_gba: &mut agb::Gba) {
+        let unique = PuzzleSize::_6x6.games(3);
+        assert!(unique.unique);
+        let cells = answer(&unique);
+        assert!(cells.iter().flatten().all(|&cell| cell != Cell::Unknown));
+
+        // 6x6 #12 has more than one solution, cells it can't decide are left unknown
+        let ambiguous = PuzzleSize::_6x6.games(11);
+        assert!(!ambiguous.unique);
+        let cells = answer(&ambiguous);
+        assert_eq!(cells[0][3], Cell::Unknown);
+        assert!(cells[3].iter().all(|&cell| cell != Cell::Unknown));
+    }
+}
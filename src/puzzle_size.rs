@@ -1,133 +1,341 @@
-use crate::{bg_gfx, nonos};
-use agb::display::object::Tag;
+use crate::nonos::generator;
+use crate::{bg_gfx, nonos, sprites};
+use agb::display::object::{Sprite, Tag};
 use agb::display::tile_data::TileData;
 
+/// A category of puzzles, the index of its entry in [SIZES]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-pub enum PuzzleSize {
-    _6x6,
-    _8x8,
-    _10x10,
-    _12x12,
-    _20x10,
-    _22x12,
-}
+pub struct PuzzleSize(usize);
 
-pub const MAX_ROWS: usize = 12;
-pub const MAX_COLS: usize = 22;
+pub const MAX_ROWS: usize = 25;
+/// At most 32 so each row of a solution fits in a u32
+pub const MAX_COLS: usize = 30;
 pub const CLUES_PER_ROW: usize = 8;
 pub const CLUES_PER_COL: usize = 8;
 
-#[derive(Debug)]
+/// Which puzzle of a size is being played
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PuzzleId {
+    /// Index of one of the puzzles in assets
+    Set(usize),
+    /// Generated from this seed for endless mode
+    Endless(u32),
+}
+
+impl PuzzleId {
+    /// Index of the puzzle in assets, None for generated puzzles which aren't saved
+    pub fn set_idx(self) -> Option<usize> {
+        match self {
+            PuzzleId::Set(idx) => Some(idx),
+            PuzzleId::Endless(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Puzzle {
-    pub row_clues: &'static [[u8; CLUES_PER_ROW]; MAX_ROWS],
-    pub col_clues: &'static [[u8; CLUES_PER_COL]; MAX_COLS],
+    pub row_clues: [[u8; CLUES_PER_ROW]; MAX_ROWS],
+    pub col_clues: [[u8; CLUES_PER_COL]; MAX_COLS],
     pub width: usize,
     pub height: usize,
+    /// A mask per row (bit x is column x)
+    pub solution: [u32; MAX_ROWS],
+    /// False if the clues allow other solutions, so `solution` can't be used to check guesses
+    pub unique: bool,
+    pub title: Option<&'static str>,
+    pub author: Option<&'static str>,
+    /// 1 (easiest) to 5, from the puzzle's header or rated by the build script
+    pub difficulty: u8,
+    /// Only show the title once the puzzle has been solved
+    pub hidden: bool,
+}
+
+impl Puzzle {
+    pub fn is_filled(&self, x: usize, y: usize) -> bool {
+        self.solution[y] & (1 << x) != 0
+    }
+}
+
+/// The puzzles in one `.nonos` file, generated by the build script
+pub struct PuzzleSet {
+    pub width: usize,
+    pub height: usize,
+    pub count: usize,
+    /// Offset of this sizes puzzles in the completed flags and best times
+    pub save_idx: usize,
+    /// Offset of this sizes boards in the in progress store
+    pub progress_idx: usize,
+    pub images: &'static Tag,
+    pub game: fn(usize) -> Puzzle,
+}
+
+/// Where the puzzle images go in the puzzle menu and win screen, shared by sizes with the
+/// same shape of image
+pub struct ImageLayout {
+    /// Puzzle menu buttons
+    pub bg: &'static TileData,
+    /// Top left tile of each button in the puzzle menu, by row
+    pub buttons: &'static [&'static [(u8, u8)]],
+    pub button_size: (u8, u8),
+    /// Drawn side by side in place of the image until the puzzle is solved
+    pub empty: &'static [&'static Tag],
+    /// Top left of the image on the win screen, in pixels
+    pub win_pos: (i32, i32),
+}
+
+/// Lines drawn over the board every few cells
+pub enum BoardGrid {
+    None,
+    /// Drawn once, for boards that fit on screen
+    Fixed(&'static TileData),
+    /// Follows the camera on boards that scroll
+    Scrolling,
+}
+
+/// Everything that differs between sizes
+pub struct SizeInfo {
+    pub set: &'static PuzzleSet,
+    pub layout: &'static ImageLayout,
+    /// Puzzle menu title
+    pub title: &'static TileData,
+    /// Clue backgrounds in the game
+    pub game: &'static TileData,
+    pub grid: BoardGrid,
+    /// Column and row of its button in the main menu
+    pub main_menu: (usize, usize),
+    /// Byte in the save header for its grid setting
+    pub grid_save_idx: usize,
 }
 
+#[rustfmt::skip]
+static SQUARE: ImageLayout = ImageLayout {
+    bg: &bg_gfx::board_sq,
+    buttons: &[
+        &[(1, 5), (5, 5), (9, 5), (13, 5), (17, 5), (21, 5), (25, 5)],
+        &[(1, 10), (5, 10), (9, 10), (13, 10), (17, 10), (21, 10), (25, 10)],
+        &[(1, 15), (5, 15), (9, 15), (13, 15),(17,15), (21, 15), (25, 15)],
+    ],
+    button_size: (3, 3),
+    empty: &[&sprites::QUESTION_SQ],
+    win_pos: (104, 36),
+};
+
+#[rustfmt::skip]
+static RECT: ImageLayout = ImageLayout {
+    bg: &bg_gfx::board_rect,
+    buttons: &[
+        &[(1, 5), (8, 5), (16, 5), (23, 5)],
+        &[(1, 10), (8, 10), (16, 10), (23, 10)],
+        &[(1, 15), (8, 15), (16, 15), (23, 15)],
+    ],
+    button_size: (5, 3),
+    empty: &[&sprites::QUESTION_RECT_L, &sprites::QUESTION_RECT_R],
+    win_pos: (88, 32),
+};
+
+/// Images are twice the size of the buttons and shrunk to fit
+#[rustfmt::skip]
+static BIG: ImageLayout = ImageLayout {
+    bg: &bg_gfx::board_big,
+    buttons: &[
+        &[(9, 5), (13, 5), (17, 5)],
+    ],
+    button_size: (3, 3),
+    empty: &[&sprites::QUESTION_SQ],
+    win_pos: (88, 20),
+};
+
+/// Every size in the order they're listed, adding a size only needs its `.nonos` file,
+/// images and an entry here (plus a save version for its grid setting)
+pub static SIZES: [SizeInfo; 7] = [
+    SizeInfo {
+        set: &nonos::_6x6::SET,
+        layout: &SQUARE,
+        title: &bg_gfx::menu_6x6,
+        game: &bg_gfx::game_6x6,
+        grid: BoardGrid::None,
+        main_menu: (0, 0),
+        grid_save_idx: 1,
+    },
+    SizeInfo {
+        set: &nonos::_8x8::SET,
+        layout: &SQUARE,
+        title: &bg_gfx::menu_8x8,
+        game: &bg_gfx::game_8x8,
+        grid: BoardGrid::Fixed(&bg_gfx::grid_8x8),
+        main_menu: (1, 0),
+        grid_save_idx: 2,
+    },
+    SizeInfo {
+        set: &nonos::_10x10::SET,
+        layout: &SQUARE,
+        title: &bg_gfx::menu_10x10,
+        game: &bg_gfx::game_10x10,
+        grid: BoardGrid::Fixed(&bg_gfx::grid_10x10),
+        main_menu: (2, 0),
+        grid_save_idx: 3,
+    },
+    SizeInfo {
+        set: &nonos::_12x12::SET,
+        layout: &SQUARE,
+        title: &bg_gfx::menu_12x12,
+        game: &bg_gfx::game_12x12,
+        grid: BoardGrid::Fixed(&bg_gfx::grid_12x12),
+        main_menu: (0, 1),
+        grid_save_idx: 4,
+    },
+    SizeInfo {
+        set: &nonos::_20x10::SET,
+        layout: &RECT,
+        title: &bg_gfx::menu_20x10,
+        game: &bg_gfx::game_20x10,
+        grid: BoardGrid::Fixed(&bg_gfx::grid_20x10),
+        main_menu: (1, 1),
+        grid_save_idx: 5,
+    },
+    SizeInfo {
+        set: &nonos::_22x12::SET,
+        layout: &RECT,
+        title: &bg_gfx::menu_22x12,
+        game: &bg_gfx::game_22x12,
+        grid: BoardGrid::Fixed(&bg_gfx::grid_22x12),
+        main_menu: (2, 1),
+        grid_save_idx: 6,
+    },
+    SizeInfo {
+        set: &nonos::_25x25::SET,
+        layout: &BIG,
+        title: &bg_gfx::menu_25x25,
+        // only the part of the board on screen has clue backgrounds
+        game: &bg_gfx::game_22x12,
+        grid: BoardGrid::Scrolling,
+        main_menu: (1, 2),
+        grid_save_idx: 16,
+    },
+];
+
+/// Names for the sizes in [SIZES]
+#[allow(non_upper_case_globals)]
 impl PuzzleSize {
-    pub fn save_idx(self) -> usize {
-        match self {
-            PuzzleSize::_6x6 => 0,
-            PuzzleSize::_8x8 => 21,
-            PuzzleSize::_10x10 => 42,
-            PuzzleSize::_12x12 => 63,
-            PuzzleSize::_20x10 => 84,
-            PuzzleSize::_22x12 => 96,
-        }
+    pub const _6x6: PuzzleSize = PuzzleSize(0);
+    pub const _8x8: PuzzleSize = PuzzleSize(1);
+    pub const _10x10: PuzzleSize = PuzzleSize(2);
+    pub const _12x12: PuzzleSize = PuzzleSize(3);
+    pub const _20x10: PuzzleSize = PuzzleSize(4);
+    pub const _22x12: PuzzleSize = PuzzleSize(5);
+    pub const _25x25: PuzzleSize = PuzzleSize(6);
+}
+
+impl PuzzleSize {
+    pub fn all() -> impl Iterator<Item = PuzzleSize> {
+        (0..SIZES.len()).map(PuzzleSize)
     }
 
-    pub fn images(self) -> &'static Tag {
-        match self {
-            PuzzleSize::_6x6 => nonos::_6x6::IMAGES,
-            PuzzleSize::_8x8 => nonos::_8x8::IMAGES,
-            PuzzleSize::_10x10 => nonos::_10x10::IMAGES,
-            PuzzleSize::_12x12 => nonos::_12x12::IMAGES,
-            PuzzleSize::_20x10 => nonos::_20x10::IMAGES,
-            PuzzleSize::_22x12 => nonos::_22x12::IMAGES,
-        }
+    /// Size with its button at `pos` in the main menu
+    pub fn at_main_menu(pos: (usize, usize)) -> Option<PuzzleSize> {
+        PuzzleSize::all().find(|size| size.info().main_menu == pos)
+    }
+
+    pub fn info(self) -> &'static SizeInfo {
+        &SIZES[self.0]
+    }
+
+    pub fn dimensions(self) -> (usize, usize) {
+        (self.info().set.width, self.info().set.height)
     }
 
     pub fn game_count(self) -> usize {
-        match self {
-            PuzzleSize::_12x12 | PuzzleSize::_10x10 | PuzzleSize::_8x8 | PuzzleSize::_6x6 => 21,
-            PuzzleSize::_20x10 | PuzzleSize::_22x12 => 12,
-        }
+        self.info().set.count
     }
 
-    pub fn bg_title(self) -> &'static TileData {
-        match self {
-            PuzzleSize::_6x6 => &bg_gfx::menu_6x6,
-            PuzzleSize::_8x8 => &bg_gfx::menu_8x8,
-            PuzzleSize::_10x10 => &bg_gfx::menu_10x10,
-            PuzzleSize::_12x12 => &bg_gfx::menu_12x12,
-            PuzzleSize::_20x10 => &bg_gfx::menu_20x10,
-            PuzzleSize::_22x12 => &bg_gfx::menu_22x12,
-        }
+    pub fn save_idx(self) -> usize {
+        self.info().set.save_idx
     }
 
-    pub fn bg_game(self) -> &'static TileData {
-        match self {
-            PuzzleSize::_6x6 => &bg_gfx::game_6x6,
-            PuzzleSize::_8x8 => &bg_gfx::game_8x8,
-            PuzzleSize::_10x10 => &bg_gfx::game_10x10,
-            PuzzleSize::_12x12 => &bg_gfx::game_12x12,
-            PuzzleSize::_20x10 => &bg_gfx::game_20x10,
-            PuzzleSize::_22x12 => &bg_gfx::game_22x12,
-        }
+    /// Offset of this sizes boards in the in progress store
+    pub fn progress_idx(self) -> usize {
+        self.info().set.progress_idx
+    }
+
+    pub fn images(self) -> &'static Tag {
+        self.info().set.images
     }
 
     pub fn games(self, idx: usize) -> Puzzle {
-        match self {
-            PuzzleSize::_6x6 => nonos::_6x6::game(idx),
-            PuzzleSize::_8x8 => nonos::_8x8::game(idx),
-            PuzzleSize::_10x10 => nonos::_10x10::game(idx),
-            PuzzleSize::_12x12 => nonos::_12x12::game(idx),
-            PuzzleSize::_20x10 => nonos::_20x10::game(idx),
-            PuzzleSize::_22x12 => nonos::_22x12::game(idx),
-        }
+        (self.info().set.game)(idx)
     }
 
-    pub fn bg(self) -> &'static TileData {
-        match self {
-            PuzzleSize::_12x12 | PuzzleSize::_10x10 | PuzzleSize::_8x8 | PuzzleSize::_6x6 => {
-                &bg_gfx::board_sq
-            }
-            PuzzleSize::_20x10 | PuzzleSize::_22x12 => &bg_gfx::board_rect,
+    pub fn puzzle(self, id: PuzzleId) -> Puzzle {
+        match id {
+            PuzzleId::Set(idx) => self.games(idx),
+            PuzzleId::Endless(seed) => generator::generate(self, seed),
         }
     }
 
-    pub fn dimensions(self) -> (usize, usize) {
-        match self {
-            PuzzleSize::_6x6 => (6, 6),
-            PuzzleSize::_8x8 => (8, 8),
-            PuzzleSize::_10x10 => (10, 10),
-            PuzzleSize::_12x12 => (12, 12),
-            PuzzleSize::_20x10 => (20, 10),
-            PuzzleSize::_22x12 => (22, 12),
-        }
+    /// Artwork shown once the puzzle is solved, generated puzzles don't have any
+    pub fn image(self, id: PuzzleId) -> Option<&'static Sprite> {
+        id.set_idx().map(|idx| self.images().sprite(idx))
+    }
+
+    /// Bytes needed to store one board at 2 bits per cell
+    pub fn progress_size(self) -> usize {
+        let (w, h) = self.dimensions();
+        (w * h).div_ceil(4)
+    }
+
+    pub fn bg_title(self) -> &'static TileData {
+        self.info().title
+    }
+
+    pub fn bg_game(self) -> &'static TileData {
+        self.info().game
+    }
+
+    pub fn bg(self) -> &'static TileData {
+        self.info().layout.bg
     }
 
-    #[rustfmt::skip]
     pub fn buttons(self) -> &'static [&'static [(u8, u8)]] {
-        match self {
-            PuzzleSize::_12x12 | PuzzleSize::_10x10 | PuzzleSize::_8x8 | PuzzleSize::_6x6 => &[
-                &[(1, 5), (5, 5), (9, 5), (13, 5), (17, 5), (21, 5), (25, 5)],
-                &[(1, 10), (5, 10), (9, 10), (13, 10), (17, 10), (21, 10), (25, 10)],
-                &[(1, 15), (5, 15), (9, 15), (13, 15),(17,15), (21, 15), (25, 15)],
-            ],
-            PuzzleSize::_20x10 | PuzzleSize::_22x12 => &[
-                &[(1, 5), (8, 5), (16, 5), (23, 5)],
-                &[(1, 10), (8, 10), (16, 10), (23, 10)],
-                &[(1, 15), (8, 15), (16, 15), (23, 15)],
-            ],
-        }
+        self.info().layout.buttons
     }
 
     pub fn button_size(self) -> (u8, u8) {
-        match self {
-            PuzzleSize::_12x12 | PuzzleSize::_10x10 | PuzzleSize::_8x8 | PuzzleSize::_6x6 => (3, 3),
-            PuzzleSize::_20x10 | PuzzleSize::_22x12 => (5, 3),
+        self.info().layout.button_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nonos::TOTAL_GAME_COUNT;
+
+    #[test_case]
+    fn names_match_table(_gba: &mut agb::Gba) {
+        assert_eq!(PuzzleSize::_6x6.dimensions(), (6, 6));
+        assert_eq!(PuzzleSize::_8x8.dimensions(), (8, 8));
+        assert_eq!(PuzzleSize::_10x10.dimensions(), (10, 10));
+        assert_eq!(PuzzleSize::_12x12.dimensions(), (12, 12));
+        assert_eq!(PuzzleSize::_20x10.dimensions(), (20, 10));
+        assert_eq!(PuzzleSize::_22x12.dimensions(), (22, 12));
+        assert_eq!(PuzzleSize::_25x25.dimensions(), (25, 25));
+    }
+
+    #[test_case]
+    fn every_puzzle_file_has_a_size(_gba: &mut agb::Gba) {
+        let games: usize = PuzzleSize::all().map(PuzzleSize::game_count).sum();
+        assert_eq!(games, TOTAL_GAME_COUNT);
+    }
+
+    #[test_case]
+    fn sizes_have_their_own_buttons_and_settings(_gba: &mut agb::Gba) {
+        for a in PuzzleSize::all() {
+            let layout = a.info().layout;
+            let buttons: usize = layout.buttons.iter().map(|row| row.len()).sum();
+            assert_eq!(buttons, a.game_count());
+            for b in PuzzleSize::all().filter(|&b| b != a) {
+                assert_ne!(a.info().main_menu, b.info().main_menu);
+                assert_ne!(a.info().grid_save_idx, b.info().grid_save_idx);
+            }
         }
     }
 }
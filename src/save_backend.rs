@@ -0,0 +1,254 @@
+use agb::save::{Error, SaveManager};
+use alloc::boxed::Box;
+#[cfg(test)]
+use alloc::{vec, vec::Vec};
+
+const SAVE_FEATURES: usize = cfg!(feature = "sram") as usize
+    + cfg!(feature = "flash64") as usize
+    + cfg!(feature = "flash128") as usize
+    + cfg!(feature = "eeprom512b") as usize
+    + cfg!(feature = "eeprom8k") as usize;
+
+const _: () = assert!(
+    SAVE_FEATURES == 1,
+    "Enable exactly one of the `sram`, `flash64`, `flash128`, `eeprom512b` or `eeprom8k` features"
+);
+
+/// Storage that save data can be read from and written to
+pub trait SaveBackend {
+    /// Size of the storage in bytes
+    fn capacity(&self) -> usize;
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), Error>;
+    /// Erases the area (if needed for the media) and then writes `data` to it
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Error>;
+}
+
+/// Sets up the save media selected by the enabled cargo feature
+pub fn cart(save: SaveManager) -> Box<dyn SaveBackend> {
+    #[cfg(feature = "sram")]
+    let backend = Sram::new(save);
+    #[cfg(feature = "flash64")]
+    let backend = Flash64K::new(save);
+    #[cfg(feature = "flash128")]
+    let backend = Flash128K::new(save);
+    #[cfg(feature = "eeprom512b")]
+    let backend = Eeprom512B::new(save);
+    #[cfg(feature = "eeprom8k")]
+    let backend = Eeprom8K::new(save);
+
+    Box::new(backend)
+}
+
+fn cart_read(save: &mut SaveManager, offset: usize, buffer: &mut [u8]) -> Result<(), Error> {
+    save.access()?.read(offset, buffer)
+}
+
+fn cart_write(save: &mut SaveManager, offset: usize, data: &[u8]) -> Result<(), Error> {
+    let mut save_data = save.access()?;
+    let mut writer = save_data.prepare_write(offset..offset + data.len())?;
+    writer.write(offset, data)
+}
+
+/// 32KiB battery backed SRAM
+#[cfg(feature = "sram")]
+pub struct Sram(SaveManager);
+
+#[cfg(feature = "sram")]
+impl Sram {
+    pub fn new(mut save: SaveManager) -> Self {
+        save.init_sram();
+        Self(save)
+    }
+}
+
+#[cfg(feature = "sram")]
+impl SaveBackend for Sram {
+    fn capacity(&self) -> usize {
+        32 * 1024
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), Error> {
+        cart_read(&mut self.0, offset, buffer)
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        cart_write(&mut self.0, offset, data)
+    }
+}
+
+/// 64KiB (512Kbit) flash
+#[cfg(feature = "flash64")]
+pub struct Flash64K(SaveManager);
+
+#[cfg(feature = "flash64")]
+impl Flash64K {
+    pub fn new(mut save: SaveManager) -> Self {
+        save.init_flash_64k();
+        Self(save)
+    }
+}
+
+#[cfg(feature = "flash64")]
+impl SaveBackend for Flash64K {
+    fn capacity(&self) -> usize {
+        64 * 1024
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), Error> {
+        cart_read(&mut self.0, offset, buffer)
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        cart_write(&mut self.0, offset, data)
+    }
+}
+
+/// 128KiB (1Mbit) flash
+#[cfg(feature = "flash128")]
+pub struct Flash128K(SaveManager);
+
+#[cfg(feature = "flash128")]
+impl Flash128K {
+    pub fn new(mut save: SaveManager) -> Self {
+        save.init_flash_128k();
+        Self(save)
+    }
+}
+
+#[cfg(feature = "flash128")]
+impl SaveBackend for Flash128K {
+    fn capacity(&self) -> usize {
+        128 * 1024
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), Error> {
+        cart_read(&mut self.0, offset, buffer)
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        cart_write(&mut self.0, offset, data)
+    }
+}
+
+/// 512 byte EEPROM, too small for in progress boards and best times so only settings and completion are kept
+#[cfg(feature = "eeprom512b")]
+pub struct Eeprom512B(SaveManager);
+
+#[cfg(feature = "eeprom512b")]
+impl Eeprom512B {
+    pub fn new(mut save: SaveManager) -> Self {
+        save.init_eeprom_512b();
+        Self(save)
+    }
+}
+
+#[cfg(feature = "eeprom512b")]
+impl SaveBackend for Eeprom512B {
+    fn capacity(&self) -> usize {
+        512
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), Error> {
+        cart_read(&mut self.0, offset, buffer)
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        cart_write(&mut self.0, offset, data)
+    }
+}
+
+/// 8KiB EEPROM
+#[cfg(feature = "eeprom8k")]
+pub struct Eeprom8K(SaveManager);
+
+#[cfg(feature = "eeprom8k")]
+impl Eeprom8K {
+    pub fn new(mut save: SaveManager) -> Self {
+        save.init_eeprom_8k();
+        Self(save)
+    }
+}
+
+#[cfg(feature = "eeprom8k")]
+impl SaveBackend for Eeprom8K {
+    fn capacity(&self) -> usize {
+        8 * 1024
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), Error> {
+        cart_read(&mut self.0, offset, buffer)
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        cart_write(&mut self.0, offset, data)
+    }
+}
+
+/// RAM backed save, for testing save logic without a cart
+#[cfg(test)]
+pub struct MemorySave {
+    data: Vec<u8>,
+    /// Fail every read and write
+    pub broken: bool,
+    /// Number of writes that succeed before the rest are cut off halfway, like losing power mid-write
+    pub writes_before_tear: Option<usize>,
+}
+
+#[cfg(test)]
+impl MemorySave {
+    /// Starts filled with 0xFF, like erased flash
+    pub fn new(capacity: usize) -> Self {
+        Self::from_bytes(vec![0xFF; capacity])
+    }
+
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            broken: false,
+            writes_before_tear: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl SaveBackend for MemorySave {
+    fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), Error> {
+        if self.broken {
+            return Err(Error::OperationTimedOut);
+        }
+        let src = self
+            .data
+            .get(offset..offset + buffer.len())
+            .ok_or(Error::OutOfBounds)?;
+        buffer.copy_from_slice(src);
+        Ok(())
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        if self.broken {
+            return Err(Error::WriteError);
+        }
+        let dest = self
+            .data
+            .get_mut(offset..offset + data.len())
+            .ok_or(Error::OutOfBounds)?;
+        match &mut self.writes_before_tear {
+            Some(0) => {
+                let len = data.len() / 2;
+                dest[..len].copy_from_slice(&data[..len]);
+                Err(Error::WriteError)
+            }
+            remaining => {
+                if let Some(count) = remaining {
+                    *count -= 1;
+                }
+                dest.copy_from_slice(data);
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use crate::save_backend::SaveBackend;
+use crate::save_migration::layout_size;
+use crate::settings_data::{SAVE_DATA_SIZE, SAVE_IDX_PROGRESS};
+use agb::eprintln;
+use agb::save::Error;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+const MAGIC: [u8; 4] = *b"NONO";
+
+const HEADER_IDX_MAGIC: usize = 0;
+const HEADER_IDX_SEQUENCE: usize = 4;
+const HEADER_IDX_CRC: usize = 8;
+const HEADER_SIZE: usize = 12;
+
+/// Banks start on 4K boundaries so that erasing one flash sector never touches the other bank
+const MAX_BANK_STRIDE: usize = 4096;
+const BANK_COUNT: usize = 2;
+/// Bank stride on the smallest supported media (EEPROM 512B)
+const MIN_BANK_STRIDE: usize = 512 / BANK_COUNT;
+
+const _: () = assert!(
+    SAVE_DATA_SIZE + HEADER_SIZE <= MAX_BANK_STRIDE,
+    "save data no longer fits in a bank, in progress boards or best times would be cut off"
+);
+const _: () = assert!(
+    SAVE_IDX_PROGRESS + HEADER_SIZE <= MIN_BANK_STRIDE,
+    "settings and completed puzzles no longer fit in a bank on the smallest media"
+);
+
+/// Save data is written to two alternating banks, each with a sequence number
+/// and CRC, so that losing power mid-write only loses the latest save
+///
+/// On media too small to hold the whole save in a bank everything from the in progress
+/// boards on (the boards and best times) is dropped and loads as empty, so no board is
+/// ever saved partially
+///
+/// The CRC covers the layout size of the version the bank was written with (cut down to
+/// what fits on small media), so banks from before a layout change stay valid and are
+/// upgraded by the migration
+pub struct SaveBanks {
+    backend: Box<dyn SaveBackend>,
+    /// Bank and sequence number of the last successful write or load
+    newest: Option<(usize, u32)>,
+}
+
+impl SaveBanks {
+    pub fn new(backend: Box<dyn SaveBackend>) -> Self {
+        if Self::payload_size(backend.capacity()) < SAVE_DATA_SIZE {
+            eprintln!("Save media too small, in progress boards and best times won't be saved");
+        }
+        Self {
+            backend,
+            newest: None,
+        }
+    }
+
+    fn stride(capacity: usize) -> usize {
+        (capacity / BANK_COUNT).min(MAX_BANK_STRIDE)
+    }
+
+    fn payload_size(capacity: usize) -> usize {
+        if Self::stride(capacity) - HEADER_SIZE >= SAVE_DATA_SIZE {
+            SAVE_DATA_SIZE
+        } else {
+            SAVE_IDX_PROGRESS
+        }
+    }
+
+    /// Reads the newest valid bank
+    ///
+    /// Falls back to the pre-bank layout (save data at offset 0) if neither
+    /// bank is valid, which is then upgraded by the normal save migration
+    pub fn load(&mut self) -> Result<[u8; SAVE_DATA_SIZE], Error> {
+        let capacity = self.backend.capacity();
+        let mut newest: Option<(usize, u32, Vec<u8>)> = None;
+        for bank in 0..BANK_COUNT {
+            match self.read_bank(bank)? {
+                Some((sequence, payload)) => {
+                    if newest
+                        .as_ref()
+                        .is_none_or(|(_, newest_seq, _)| is_newer(sequence, *newest_seq))
+                    {
+                        newest = Some((bank, sequence, payload));
+                    }
+                }
+                None => eprintln!("Save bank {bank} invalid"),
+            }
+        }
+
+        let mut bytes = [0_u8; SAVE_DATA_SIZE];
+        match newest {
+            Some((bank, sequence, payload)) => {
+                let len = payload.len().min(SAVE_DATA_SIZE);
+                bytes[..len].copy_from_slice(&payload[..len]);
+                self.newest = Some((bank, sequence));
+            }
+            None => {
+                let len = SAVE_DATA_SIZE.min(capacity);
+                self.backend.read(0, &mut bytes[..len])?;
+                self.newest = None;
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// Writes to the bank not holding the newest save
+    pub fn write(&mut self, bytes: &[u8; SAVE_DATA_SIZE]) -> Result<(), Error> {
+        let (bank, sequence) = match self.newest {
+            Some((bank, sequence)) => ((bank + 1) % BANK_COUNT, sequence.wrapping_add(1)),
+            // bank 0 overlaps the pre-bank layout so keep that until bank 1 is written
+            None => (1, 0),
+        };
+
+        let capacity = self.backend.capacity();
+        let payload = &bytes[..Self::payload_size(capacity)];
+        let mut data = vec![0_u8; HEADER_SIZE + payload.len()];
+        data[HEADER_IDX_MAGIC..HEADER_IDX_SEQUENCE].copy_from_slice(&MAGIC);
+        data[HEADER_IDX_SEQUENCE..HEADER_IDX_CRC].copy_from_slice(&sequence.to_le_bytes());
+        data[HEADER_IDX_CRC..HEADER_SIZE].copy_from_slice(&crc32(payload).to_le_bytes());
+        data[HEADER_SIZE..].copy_from_slice(payload);
+
+        self.backend.write(bank * Self::stride(capacity), &data)?;
+
+        self.newest = Some((bank, sequence));
+        Ok(())
+    }
+
+    /// Returns the sequence number and payload if the bank has valid data
+    fn read_bank(&mut self, bank: usize) -> Result<Option<(u32, Vec<u8>)>, Error> {
+        let capacity = self.backend.capacity();
+        let stride = Self::stride(capacity);
+        let mut data = vec![0_u8; stride];
+        self.backend.read(bank * stride, &mut data)?;
+
+        if data[HEADER_IDX_MAGIC..HEADER_IDX_SEQUENCE] != MAGIC {
+            return Ok(None);
+        }
+
+        let sequence = u32::from_le_bytes(
+            data[HEADER_IDX_SEQUENCE..HEADER_IDX_CRC]
+                .try_into()
+                .expect("Invalid bank header (sequence)"),
+        );
+        let crc = u32::from_le_bytes(
+            data[HEADER_IDX_CRC..HEADER_SIZE]
+                .try_into()
+                .expect("Invalid bank header (crc)"),
+        );
+        let mut payload = data.split_off(HEADER_SIZE);
+        let Some(len) = layout_size(payload[0]) else {
+            return Ok(None);
+        };
+        // small media only wrote the data before the in progress boards
+        payload.truncate(len.min(Self::payload_size(capacity)));
+
+        if crc32(&payload) != crc {
+            return Ok(None);
+        }
+
+        Ok(Some((sequence, payload)))
+    }
+}
+
+/// Sequence numbers wrap, so newer means less than half the range ahead
+fn is_newer(sequence: u32, than: u32) -> bool {
+    (sequence.wrapping_sub(than) as i32) > 0
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// CRC-32 (IEEE)
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        CRC_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle_size::PuzzleSize;
+    use crate::save_backend::MemorySave;
+    use crate::scenes::scene_game_puzzle::Guess;
+    use crate::settings_data::{MAX_VOLUME, SettingsData};
+
+    fn save_with(music_volume: u8) -> [u8; SAVE_DATA_SIZE] {
+        let mut data = SettingsData::default();
+        data.music_volume = music_volume;
+        data.as_bytes()
+    }
+
+    #[test_case]
+    fn crc_matches_reference(_gba: &mut agb::Gba) {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test_case]
+    fn sequence_wraps(_gba: &mut agb::Gba) {
+        assert!(is_newer(1, 0));
+        assert!(!is_newer(0, 1));
+        assert!(is_newer(0, u32::MAX));
+    }
+
+    #[test_case]
+    fn loads_newest_bank(_gba: &mut agb::Gba) {
+        let mut banks = SaveBanks::new(Box::new(MemorySave::new(32 * 1024)));
+        banks.write(&save_with(0)).unwrap();
+        banks.write(&save_with(MAX_VOLUME)).unwrap();
+        banks.write(&save_with(0)).unwrap();
+
+        let bytes = banks.load().unwrap();
+        assert_eq!(banks.newest, Some((1, 2)));
+        assert_eq!(SettingsData::from_bytes(bytes).music_volume, 0);
+    }
+
+    #[test_case]
+    fn torn_write_keeps_previous_save(_gba: &mut agb::Gba) {
+        let mut save = MemorySave::new(32 * 1024);
+        save.writes_before_tear = Some(1);
+        let mut banks = SaveBanks::new(Box::new(save));
+        banks.write(&save_with(0)).unwrap();
+        assert!(banks.write(&save_with(MAX_VOLUME)).is_err());
+
+        let bytes = banks.load().unwrap();
+        assert_eq!(banks.newest, Some((1, 0)));
+        assert_eq!(SettingsData::from_bytes(bytes).music_volume, 0);
+    }
+
+    #[test_case]
+    fn falls_back_to_legacy_layout(_gba: &mut agb::Gba) {
+        let mut data = vec![0xFF; 32 * 1024];
+        data[..SAVE_DATA_SIZE].copy_from_slice(&save_with(0));
+        let mut banks = SaveBanks::new(Box::new(MemorySave::from_bytes(data)));
+
+        let bytes = banks.load().unwrap();
+        assert_eq!(banks.newest, None);
+        assert_eq!(SettingsData::from_bytes(bytes).music_volume, 0);
+
+        // first write must not overwrite the legacy save
+        banks.write(&save_with(MAX_VOLUME)).unwrap();
+        assert_eq!(banks.newest, Some((1, 0)));
+    }
+
+    #[test_case]
+    fn loads_bank_from_older_layout(_gba: &mut agb::Gba) {
+        let mut v4 = vec![0_u8; 3318];
+        v4[0] = 4;
+        v4[7] = 1; //music
+        v4[120] = 0b01; //first cell of 6x6 #1 filled
+        let mut data = vec![0xFF; 32 * 1024];
+        data[..4].copy_from_slice(&MAGIC);
+        data[4..8].copy_from_slice(&7_u32.to_le_bytes());
+        data[8..12].copy_from_slice(&crc32(&v4).to_le_bytes());
+        data[12..12 + v4.len()].copy_from_slice(&v4);
+        let mut banks = SaveBanks::new(Box::new(MemorySave::from_bytes(data)));
+
+        let loaded = SettingsData::from_bytes(banks.load().unwrap());
+        assert_eq!(banks.newest, Some((0, 7)));
+        assert_eq!(loaded.music_volume, MAX_VOLUME);
+        assert_eq!(
+            loaded.progress(PuzzleSize::_6x6, 0).unwrap()[0][0],
+            Guess::Filled
+        );
+    }
+
+    #[test_case]
+    fn small_media_drops_progress(_gba: &mut agb::Gba) {
+        let mut data = SettingsData::default();
+        data.music_volume = 0;
+        data.set_completed(PuzzleSize::_8x8, 3, false);
+        // 6x6 #0 would fit in a 512B bank and #3 would be cut off partway
+        for idx in [0, 3] {
+            data.set_progress(PuzzleSize::_6x6, idx, &vec![vec![Guess::Filled; 6]; 6]);
+            assert!(data.is_in_progress(PuzzleSize::_6x6, idx));
+        }
+        data.set_best_time(PuzzleSize::_8x8, 3, 60);
+        let mut banks = SaveBanks::new(Box::new(MemorySave::new(512)));
+        banks.write(&data.as_bytes()).unwrap();
+
+        let loaded = SettingsData::from_bytes(banks.load().unwrap());
+        assert_eq!(loaded.music_volume, 0);
+        assert_eq!(loaded.is_completed_by_size(PuzzleSize::_8x8)[3], 1);
+        assert!(!loaded.is_in_progress(PuzzleSize::_6x6, 0));
+        assert!(!loaded.is_in_progress(PuzzleSize::_6x6, 3));
+        assert_eq!(loaded.best_time(PuzzleSize::_8x8, 3), None);
+    }
+
+    #[test_case]
+    fn media_errors_are_returned(_gba: &mut agb::Gba) {
+        let mut save = MemorySave::new(32 * 1024);
+        save.broken = true;
+        let mut banks = SaveBanks::new(Box::new(save));
+
+        assert!(banks.load().is_err());
+        assert!(banks.write(&save_with(MAX_VOLUME)).is_err());
+    }
+}
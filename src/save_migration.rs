@@ -0,0 +1,381 @@
+use crate::nonos::SAVE_ORDER;
+use crate::settings_data::{SAVE_DATA_SIZE, VERSION};
+use alloc::vec;
+use alloc::vec::Vec;
+
+// Layouts are frozen here rather than derived from settings_data so that
+// changing the current layout can't silently change how old saves are read
+
+/// v3: 12 byte header (version, grid x6, music, sfx, help, 2 reserved), 108 completed flags
+const V3_SIZE: usize = 120;
+/// v4: v3 + 3198 bytes of in progress boards
+const V4_SIZE: usize = V3_SIZE + 3198;
+/// v5: v4 with a 16 byte header (v3 header, check placement, 3 reserved written as 0).
+/// Lives mode in byte 13 and a no mistakes bit in the completed flags were first written
+/// while v5 was current without a new version, older v5 saves read as lives mode off and
+/// no puzzle finished without mistakes
+const V5_SIZE: usize = V4_SIZE + 4;
+/// v6: v5 + 216 bytes of best times (u16 seconds for each puzzle). Play time in minutes in
+/// bytes 14 and 15 and a no help bit in the completed flags were first written while v6 was
+/// current without a new version, older saves read as 0 minutes played and every puzzle
+/// completed with help
+const V6_SIZE: usize = V5_SIZE + 216;
+/// v7: v6 with an 18 byte header (v5 header, 25x25 grid, reserved) and the 3 25x25
+/// puzzles added to the end of the completed flags, in progress boards and best times
+const V7_SIZE: usize = V6_SIZE + 2 + 3 + 3 * 157 + 3 * 2;
+/// v8: v7 with music and sfx volumes (0 to 10) in bytes 10 and 11 instead of on/off in 7 and 8
+const V8_SIZE: usize = V7_SIZE;
+/// v9: v8 + 28 bytes of lives lost on each in progress board (2 bits for each puzzle)
+const V9_SIZE: usize = V8_SIZE + 28;
+/// v10: v9 with a 22 byte header (v9 header, current and best win streaks as u16s)
+const V10_SIZE: usize = V9_SIZE + 4;
+
+const OLDEST_VERSION: u8 = 3;
+
+/// Size of the data for each version, indexed by `version - OLDEST_VERSION`
+const LAYOUT_SIZES: [usize; 8] = [
+    V3_SIZE, V4_SIZE, V5_SIZE, V6_SIZE, V7_SIZE, V8_SIZE, V9_SIZE, V10_SIZE,
+];
+
+const _: () = assert!(
+    LAYOUT_SIZES[LAYOUT_SIZES.len() - 1] == SAVE_DATA_SIZE,
+    "save data size changed (puzzles added or removed?), add a new version and migration"
+);
+
+/// Width, height and puzzle count of each size in the current layout, in save order
+///
+/// Each size's puzzles are saved after the ones before it, so adding a puzzle to one size
+/// and removing one from another moves them without changing the total size
+const CURRENT_SIZES: [(usize, usize, usize); 7] = [
+    (6, 6, 21),
+    (8, 8, 21),
+    (10, 10, 21),
+    (12, 12, 21),
+    (20, 10, 12),
+    (22, 12, 12),
+    (25, 25, 3),
+];
+
+const _: () = assert!(
+    same_sizes(&CURRENT_SIZES, SAVE_ORDER),
+    "puzzles were added, removed or moved between sizes, add a new version and migration"
+);
+
+const fn same_sizes(a: &[(usize, usize, usize)], b: &[(usize, usize, usize)]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i].0 != b[i].0 || a[i].1 != b[i].1 || a[i].2 != b[i].2 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+type Migration = fn(&[u8]) -> Vec<u8>;
+
+/// Upgrades from the version at `version - OLDEST_VERSION` to the next one
+const MIGRATIONS: [Migration; 7] = [
+    v3_to_v4, v4_to_v5, v5_to_v6, v6_to_v7, v7_to_v8, v8_to_v9, v9_to_v10,
+];
+
+/// Upgrades save data from any known version to the current layout
+///
+/// Returns None if the version is not recognised (blank or corrupt save, or
+/// older than the first version with a known layout)
+pub fn migrate(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut version = bytes[0];
+    let mut data = bytes[..layout_size(version)?].to_vec();
+    while version < VERSION {
+        data = MIGRATIONS[(version - OLDEST_VERSION) as usize](&data);
+        version += 1;
+        data[0] = version;
+    }
+
+    debug_assert_eq!(data.len(), SAVE_DATA_SIZE);
+    Some(data)
+}
+
+/// Size of the save data written by `version`, None if the version is not recognised
+pub fn layout_size(version: u8) -> Option<usize> {
+    if !(OLDEST_VERSION..=VERSION).contains(&version) {
+        return None;
+    }
+    Some(LAYOUT_SIZES[(version - OLDEST_VERSION) as usize])
+}
+
+/// Adds empty in progress boards after the completed flags
+fn v3_to_v4(bytes: &[u8]) -> Vec<u8> {
+    let mut output = vec![0; V4_SIZE];
+    output[..V3_SIZE].copy_from_slice(bytes);
+    output
+}
+
+/// Grows the header by 4 bytes, check placement starts off
+fn v4_to_v5(bytes: &[u8]) -> Vec<u8> {
+    let mut output = vec![0; V5_SIZE];
+    output[..12].copy_from_slice(&bytes[..12]);
+    output[16..].copy_from_slice(&bytes[12..]);
+    output
+}
+
+/// Adds best times after the in progress boards, none are set
+fn v5_to_v6(bytes: &[u8]) -> Vec<u8> {
+    let mut output = vec![0; V6_SIZE];
+    output[..V5_SIZE].copy_from_slice(bytes);
+    output
+}
+
+/// Moves each section along to make room for the 25x25 puzzles, their grid starts on
+/// like the other sizes
+fn v6_to_v7(bytes: &[u8]) -> Vec<u8> {
+    let mut output = vec![0; V7_SIZE];
+    output[..16].copy_from_slice(&bytes[..16]);
+    output[16] = 1;
+    // completed flags, in progress boards and best times
+    output[18..126].copy_from_slice(&bytes[16..124]);
+    output[129..3327].copy_from_slice(&bytes[124..3322]);
+    output[3798..4014].copy_from_slice(&bytes[3322..V6_SIZE]);
+    output
+}
+
+/// Music and sfx that were on start at full volume
+fn v7_to_v8(bytes: &[u8]) -> Vec<u8> {
+    let mut output = bytes.to_vec();
+    output[10] = if bytes[7] > 0 { 10 } else { 0 };
+    output[11] = if bytes[8] > 0 { 10 } else { 0 };
+    output[7] = 0;
+    output[8] = 0;
+    output
+}
+
+/// Adds lives lost after the best times, boards saved before lose none
+fn v8_to_v9(bytes: &[u8]) -> Vec<u8> {
+    let mut output = vec![0; V9_SIZE];
+    output[..V8_SIZE].copy_from_slice(bytes);
+    output
+}
+
+/// Grows the header by 4 bytes, streaks start at 0
+fn v9_to_v10(bytes: &[u8]) -> Vec<u8> {
+    let mut output = vec![0; V10_SIZE];
+    output[..18].copy_from_slice(&bytes[..18]);
+    output[22..].copy_from_slice(&bytes[18..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle_size::PuzzleSize;
+    use crate::scenes::scene_game_puzzle::Guess;
+    use crate::settings_data::{HelpLevel, MAX_VOLUME, SettingsData};
+
+    fn v3_save() -> [u8; SAVE_DATA_SIZE] {
+        let mut bytes = [0xFF; SAVE_DATA_SIZE];
+        bytes[..V3_SIZE].fill(0);
+        bytes[0] = 3;
+        bytes[1..7].copy_from_slice(&[1, 0, 1, 0, 1, 1]);
+        bytes[7] = 0; //music
+        bytes[8] = 1; //sfx
+        bytes[9] = 3; //help
+        bytes[12] = 1; //6x6 #1
+        bytes[12 + 21 + 4] = 1; //8x8 #5
+        bytes[12 + 96 + 11] = 1; //22x12 #12
+        bytes
+    }
+
+    #[test_case]
+    fn v3_keeps_settings(_gba: &mut agb::Gba) {
+        let data = SettingsData::from_bytes(v3_save());
+
+        assert_eq!(data.music_volume, 0);
+        assert_eq!(data.sfx_volume, MAX_VOLUME);
+        assert_eq!(data.help_level, HelpLevel::Solvable);
+        assert!(data.grid_enabled[&PuzzleSize::_6x6]);
+        assert!(!data.grid_enabled[&PuzzleSize::_8x8]);
+        assert!(data.grid_enabled[&PuzzleSize::_10x10]);
+        assert!(!data.grid_enabled[&PuzzleSize::_12x12]);
+        assert!(data.grid_enabled[&PuzzleSize::_20x10]);
+        assert!(data.grid_enabled[&PuzzleSize::_22x12]);
+    }
+
+    #[test_case]
+    fn v3_keeps_completed(_gba: &mut agb::Gba) {
+        let data = SettingsData::from_bytes(v3_save());
+
+        let completed_6x6 = data.is_completed_by_size(PuzzleSize::_6x6);
+        assert_eq!(completed_6x6[0], 1);
+        assert!(completed_6x6[1..].iter().all(|&v| v == 0));
+        let completed_8x8 = data.is_completed_by_size(PuzzleSize::_8x8);
+        assert_eq!(completed_8x8[4], 1);
+        assert_eq!(completed_8x8.iter().filter(|&&v| v > 0).count(), 1);
+        assert_eq!(data.is_completed_by_size(PuzzleSize::_22x12)[11], 1);
+        assert!(
+            data.is_completed_by_size(PuzzleSize::_12x12)
+                .iter()
+                .all(|&v| v == 0)
+        );
+    }
+
+    #[test_case]
+    fn v3_has_no_progress(_gba: &mut agb::Gba) {
+        let data = SettingsData::from_bytes(v3_save());
+
+        assert!(
+            data.is_in_progress_by_size(PuzzleSize::_22x12)
+                .iter()
+                .all(|&v| !v)
+        );
+    }
+
+    #[test_case]
+    fn v4_keeps_completed_and_progress(_gba: &mut agb::Gba) {
+        let mut bytes = [0xFF; SAVE_DATA_SIZE];
+        bytes[..V4_SIZE].copy_from_slice(&v3_to_v4(&v3_save()[..V3_SIZE]));
+        bytes[0] = 4;
+        bytes[V3_SIZE] = 0b10; //first cell of 6x6 #1 marked
+        let data = SettingsData::from_bytes(bytes);
+
+        assert!(!data.check_placement);
+        assert_eq!(data.music_volume, 0);
+        assert_eq!(data.is_completed_by_size(PuzzleSize::_8x8)[4], 1);
+        let progress = data.progress(PuzzleSize::_6x6, 0).unwrap();
+        assert_eq!(progress[0][0], Guess::Marked);
+        assert_eq!(progress[0][1], Guess::Empty);
+    }
+
+    #[test_case]
+    fn v6_makes_room_for_25x25(_gba: &mut agb::Gba) {
+        let mut bytes = [0xFF; SAVE_DATA_SIZE];
+        bytes[..V6_SIZE].fill(0);
+        bytes[0] = 6;
+        bytes[6] = 1; //22x12 grid
+        bytes[16 + 107] = 1; //22x12 #12
+        bytes[16 + 108] = 0b10; //first cell of 6x6 #1 marked
+        bytes[V6_SIZE - 2..V6_SIZE].copy_from_slice(&90_u16.to_le_bytes()); //22x12 #12
+        let data = SettingsData::from_bytes(bytes);
+
+        assert!(data.grid_enabled[&PuzzleSize::_22x12]);
+        assert!(data.grid_enabled[&PuzzleSize::_25x25]);
+        assert_eq!(data.is_completed_by_size(PuzzleSize::_22x12)[11], 1);
+        assert_eq!(data.best_time(PuzzleSize::_22x12, 11), Some(90));
+        assert_eq!(
+            data.progress(PuzzleSize::_6x6, 0).unwrap()[0][0],
+            Guess::Marked
+        );
+        assert!(
+            data.is_completed_by_size(PuzzleSize::_25x25)
+                .iter()
+                .all(|&v| v == 0)
+        );
+        assert!(
+            data.is_in_progress_by_size(PuzzleSize::_25x25)
+                .iter()
+                .all(|&v| !v)
+        );
+        assert_eq!(data.best_time(PuzzleSize::_25x25, 0), None);
+    }
+
+    #[test_case]
+    fn v7_turns_sound_settings_into_volumes(_gba: &mut agb::Gba) {
+        let mut bytes = [0; SAVE_DATA_SIZE];
+        bytes[0] = 7;
+        bytes[7] = 1; //music
+        bytes[8] = 0; //sfx
+        let data = SettingsData::from_bytes(bytes);
+
+        assert_eq!(data.music_volume, MAX_VOLUME);
+        assert_eq!(data.sfx_volume, 0);
+        let bytes = data.as_bytes();
+        assert_eq!(bytes[7..9], [0, 0]);
+        assert_eq!(bytes[10..12], [MAX_VOLUME, 0]);
+    }
+
+    #[test_case]
+    fn v8_loses_no_lives(_gba: &mut agb::Gba) {
+        let mut bytes = [0xFF; SAVE_DATA_SIZE];
+        bytes[..V8_SIZE].fill(0);
+        bytes[0] = 8;
+        bytes[129] = 0b10; //first cell of 6x6 #1 marked
+        let data = SettingsData::from_bytes(bytes);
+
+        assert!(data.is_in_progress(PuzzleSize::_6x6, 0));
+        assert_eq!(data.lives_lost(PuzzleSize::_6x6, 0), 0);
+        assert_eq!(data.lives_lost(PuzzleSize::_25x25, 2), 0);
+    }
+
+    #[test_case]
+    fn v5_from_before_lives_mode_reads_reserved_bytes_as_off(_gba: &mut agb::Gba) {
+        let mut bytes = [0xFF; SAVE_DATA_SIZE];
+        bytes[..V5_SIZE].fill(0);
+        bytes[0] = 5;
+        bytes[12] = 1; //check placement
+        bytes[16] = 1; //6x6 #1
+        let data = SettingsData::from_bytes(bytes);
+
+        assert!(data.check_placement);
+        assert!(!data.lives_mode);
+        assert_eq!(data.play_minutes(), 0);
+        assert_eq!(data.is_completed_by_size(PuzzleSize::_6x6)[0], 1);
+        assert!(!data.is_no_mistakes_by_size(PuzzleSize::_6x6)[0]);
+    }
+
+    #[test_case]
+    fn v9_makes_room_for_streaks(_gba: &mut agb::Gba) {
+        let mut bytes = [0xFF; SAVE_DATA_SIZE];
+        bytes[..V9_SIZE].fill(0);
+        bytes[0] = 9;
+        bytes[13] = 1; //lives mode
+        bytes[14..16].copy_from_slice(&75_u16.to_le_bytes()); //play minutes
+        bytes[18 + 21 + 4] = 1; //8x8 #5
+        bytes[129] = 0b10; //first cell of 6x6 #1 marked
+        let data = SettingsData::from_bytes(bytes);
+
+        assert!(data.lives_mode);
+        assert_eq!(data.play_minutes(), 75);
+        assert_eq!(data.is_completed_by_size(PuzzleSize::_8x8)[4], 1);
+        assert_eq!(
+            data.progress(PuzzleSize::_6x6, 0).unwrap()[0][0],
+            Guess::Marked
+        );
+        let stats = data.statistics();
+        assert_eq!(stats.streak, 0);
+        assert_eq!(stats.best_streak, 0);
+    }
+
+    #[test_case]
+    fn migrated_save_round_trips(_gba: &mut agb::Gba) {
+        let data = SettingsData::from_bytes(v3_save());
+        let bytes = data.as_bytes();
+
+        assert_eq!(bytes[0], VERSION);
+        assert_eq!(migrate(&bytes).as_deref(), Some(&bytes[..]));
+    }
+
+    #[test_case]
+    fn unknown_version_is_rejected(_gba: &mut agb::Gba) {
+        let mut bytes = v3_save();
+        for version in [0, 1, 2, VERSION + 1, 0xFF] {
+            bytes[0] = version;
+            assert!(migrate(&bytes).is_none());
+        }
+    }
+
+    #[test_case]
+    fn unknown_version_uses_defaults(_gba: &mut agb::Gba) {
+        let data = SettingsData::from_bytes([0xFF; SAVE_DATA_SIZE]);
+
+        assert_eq!(data.music_volume, MAX_VOLUME);
+        assert_eq!(data.sfx_volume, MAX_VOLUME);
+        assert_eq!(data.help_level, HelpLevel::Full);
+        assert!(
+            data.is_completed_by_size(PuzzleSize::_6x6)
+                .iter()
+                .all(|&v| v == 0)
+        );
+    }
+}
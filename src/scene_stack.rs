@@ -0,0 +1,155 @@
+use crate::sfx::Audio;
+use crate::{Scene, SceneResult};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Open scenes, only the top one is updated and shown
+///
+/// Scenes opened over another (pause, confirm, save errors) suspend the one underneath
+/// instead of replacing it, so it carries on where it left off once they close
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new(scene: Box<dyn Scene>) -> Self {
+        Self {
+            scenes: vec![scene],
+        }
+    }
+
+    pub fn top(&mut self) -> &mut dyn Scene {
+        self.scenes.last_mut().expect("no open scenes").as_mut()
+    }
+
+    /// Closes every open scene and opens `scene`
+    pub fn replace(&mut self, scene: Box<dyn Scene>) {
+        self.scenes.clear();
+        self.scenes.push(scene);
+    }
+
+    /// Opens `scene` over the top scene, which is suspended until `scene` closes
+    pub fn push(&mut self, scene: Box<dyn Scene>) {
+        if let Some(top) = self.scenes.last_mut() {
+            top.suspend();
+        }
+        self.scenes.push(scene);
+    }
+
+    /// Closes the top scene and resumes the one under it
+    ///
+    /// Returns false if there was nothing under it, leaving no scenes open
+    pub fn pop(&mut self, result: SceneResult, audio: &mut Audio) -> bool {
+        self.scenes.pop();
+        match self.scenes.last_mut() {
+            Some(top) => {
+                top.resume(result, audio);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.scenes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SceneAction;
+    use crate::settings_data::SettingsData;
+    use agb::display::GraphicsFrame;
+    use agb::display::tiled::BackgroundId;
+    use agb::input::ButtonController;
+    use agb::sound::mixer::Frequency;
+    use alloc::format;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use core::cell::RefCell;
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    /// Records the calls made to it
+    struct LogScene {
+        name: &'static str,
+        log: Log,
+    }
+
+    impl LogScene {
+        #[allow(clippy::new_ret_no_self)]
+        fn new(name: &'static str, log: &Log) -> Box<dyn Scene> {
+            Box::new(Self {
+                name,
+                log: log.clone(),
+            })
+        }
+    }
+
+    impl Scene for LogScene {
+        fn init(&mut self, _audio: &mut Audio) {}
+
+        fn update(
+            &mut self,
+            _buttons: &ButtonController,
+            _audio: &mut Audio,
+        ) -> Option<SceneAction> {
+            None
+        }
+
+        fn show(&mut self, _graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
+            Vec::new()
+        }
+
+        fn suspend(&mut self) {
+            self.log.borrow_mut().push(format!("{} suspend", self.name));
+        }
+
+        fn resume(&mut self, result: SceneResult, _audio: &mut Audio) {
+            let entry = format!("{} resume {result:?}", self.name);
+            self.log.borrow_mut().push(entry);
+        }
+    }
+
+    #[test_case]
+    fn push_suspends_and_pop_resumes(gba: &mut agb::Gba) {
+        let mut audio = Audio::new(
+            gba.mixer.mixer(Frequency::Hz10512),
+            &SettingsData::default(),
+        );
+        let log = Log::default();
+        let mut stack = SceneStack::new(LogScene::new("game", &log));
+
+        stack.push(LogScene::new("pause", &log));
+        stack.push(LogScene::new("confirm", &log));
+        assert!(stack.pop(SceneResult::Back, &mut audio));
+        assert!(stack.pop(SceneResult::Check, &mut audio));
+
+        assert_eq!(
+            *log.borrow(),
+            [
+                "game suspend",
+                "pause suspend",
+                "pause resume Back",
+                "game resume Check",
+            ]
+        );
+        assert_eq!(stack.len(), 1);
+        assert!(!stack.pop(SceneResult::Back, &mut audio));
+    }
+
+    #[test_case]
+    fn replace_closes_every_scene(_gba: &mut agb::Gba) {
+        let log = Log::default();
+        let mut stack = SceneStack::new(LogScene::new("game", &log));
+        stack.push(LogScene::new("pause", &log));
+
+        stack.replace(LogScene::new("menu", &log));
+
+        assert_eq!(stack.len(), 1);
+        assert_eq!(*log.borrow(), ["game suspend"]);
+    }
+}
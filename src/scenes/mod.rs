@@ -1,7 +1,11 @@
 pub mod scene_confirm;
+pub mod scene_endless_menu;
+pub mod scene_game_fail;
 pub mod scene_game_pause;
 pub mod scene_game_puzzle;
 pub mod scene_game_win;
 pub mod scene_menu;
 pub mod scene_puzzle_menu;
+pub mod scene_save_error;
 pub mod scene_settings;
+pub mod scene_statistics;
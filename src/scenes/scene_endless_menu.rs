@@ -0,0 +1,130 @@
+use crate::gfx::{background_stack, show_backgrounds};
+use crate::nonos::generator::{MAX_SEED, SEED_DIGITS};
+use crate::puzzle_size::{PuzzleId, PuzzleSize};
+use crate::sfx::{Audio, Sfx};
+use crate::text::{Align, Text, TextColour};
+use crate::{Scene, SceneAction, SceneMusic, bg_gfx};
+use agb::display::GraphicsFrame;
+use agb::display::tiled::{BackgroundId, RegularBackground, VRAM_MANAGER};
+use agb::input::{Button, ButtonController};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+
+const TITLE_Y: i32 = 24;
+const LABEL_Y: i32 = 56;
+const SEED_Y: i32 = 72;
+/// Width of each seed digit, in pixels
+const DIGIT_WIDTH: i32 = 12;
+const SEED_X: i32 = (240 - DIGIT_WIDTH * SEED_DIGITS as i32) / 2;
+const HELP_Y: i32 = 104;
+
+const HELP: &str = "Up/down changes the digit\nA to play, B to go back";
+
+/// Picks the seed of a generated puzzle, so a puzzle can be shared by its number
+pub struct EndlessMenuScene {
+    background: [RegularBackground; 1],
+    size: PuzzleSize,
+    seed: u32,
+    /// Digit being changed, 0 is the left-most
+    cursor: usize,
+    text: Text,
+}
+
+impl EndlessMenuScene {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(size: PuzzleSize, seed: u32) -> Box<dyn Scene> {
+        let mut scene = Box::new(Self {
+            background: background_stack([&bg_gfx::dots]),
+            size,
+            seed: seed.min(MAX_SEED),
+            cursor: SEED_DIGITS - 1,
+            text: Text::default(),
+        });
+        scene.update_text();
+        scene
+    }
+
+    fn update_text(&mut self) {
+        let (w, h) = self.size.dimensions();
+        self.text.clear();
+        self.text
+            .add(
+                &format!("Endless {w}x{h}"),
+                (0, TITLE_Y),
+                240,
+                Align::Centre,
+                TextColour::Title,
+            )
+            .add("Seed", (0, LABEL_Y), 240, Align::Centre, TextColour::Body)
+            .add(HELP, (0, HELP_Y), 240, Align::Centre, TextColour::Body);
+
+        let digits = format!("{:0width$}", self.seed, width = SEED_DIGITS);
+        for (i, digit) in digits.char_indices() {
+            let colour = if i == self.cursor {
+                TextColour::Title
+            } else {
+                TextColour::Body
+            };
+            self.text.add(
+                &digits[i..i + digit.len_utf8()],
+                (SEED_X + i as i32 * DIGIT_WIDTH, SEED_Y),
+                DIGIT_WIDTH,
+                Align::Centre,
+                colour,
+            );
+        }
+    }
+
+    /// Value of the digit under the cursor
+    fn place(&self) -> u32 {
+        10u32.pow((SEED_DIGITS - 1 - self.cursor) as u32)
+    }
+}
+
+impl Scene for EndlessMenuScene {
+    fn init(&mut self, audio: &mut Audio) {
+        VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
+
+        audio.music(SceneMusic::Menu);
+    }
+
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction> {
+        if buttons.is_just_pressed(Button::A) {
+            audio.play(Sfx::Positive);
+            return Some(SceneAction::Game(self.size, PuzzleId::Endless(self.seed)));
+        } else if buttons.is_just_pressed(Button::B) {
+            audio.play(Sfx::Negative);
+            return Some(SceneAction::PuzzleMenu(self.size));
+        }
+
+        let place = self.place();
+        let digit = self.seed / place % 10;
+        let changed = if buttons.is_just_pressed(Button::LEFT) && self.cursor > 0 {
+            self.cursor -= 1;
+            true
+        } else if buttons.is_just_pressed(Button::RIGHT) && self.cursor < SEED_DIGITS - 1 {
+            self.cursor += 1;
+            true
+        } else if buttons.is_just_pressed(Button::UP) {
+            self.seed = self.seed - digit * place + (digit + 1) % 10 * place;
+            true
+        } else if buttons.is_just_pressed(Button::DOWN) {
+            self.seed = self.seed - digit * place + (digit + 9) % 10 * place;
+            true
+        } else {
+            false
+        };
+        if changed {
+            audio.play(Sfx::Cursor);
+            self.update_text();
+        }
+        None
+    }
+
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
+        self.text.show(graphics);
+
+        show_backgrounds(&self.background, graphics)
+    }
+}
@@ -0,0 +1,128 @@
+use crate::button_highlight::Highlight;
+use crate::direction::Direction;
+use crate::gfx::{TILE_SIZE, background_stack, button_sprites, show_backgrounds};
+use crate::puzzle_size::{PuzzleId, PuzzleSize};
+use crate::sfx::{Audio, Sfx};
+use crate::text::{Align, Text, TextColour};
+use crate::{Scene, SceneAction, bg_gfx};
+use agb::display::GraphicsFrame;
+use agb::display::object::Object;
+use agb::display::tiled::{BackgroundId, RegularBackground, VRAM_MANAGER};
+use agb::input::{Button, ButtonController};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+const BUTTON_INDEXES: [(u8, u8); 2] = [(7, 14), (16, 14)];
+const BUTTON_SIZE: (u8, u8) = (7, 3);
+const BUTTON_LABELS: [&str; 2] = ["Retry", "Quit"];
+/// Label position inside the button, in pixels
+const LABEL_OFFSET: (i32, i32) = (3, 10);
+
+const TITLE_Y: i32 = 16;
+const MESSAGE_Y: i32 = 40;
+const TEXT_WIDTH: i32 = 208;
+const TEXT_X: i32 = 16;
+
+const TITLE: &str = "Out of lives";
+const MESSAGE: &str = "The board has been cleared.";
+
+/// Shown when every life is lost in lives mode
+pub struct GameFailScene {
+    button_idx: usize,
+    puzzle_size: PuzzleSize,
+    puzzle_id: PuzzleId,
+    backgrounds: [RegularBackground; 2],
+    button_highlight_sprites: [Object; 3],
+    text: Text,
+    button_highlight: Highlight,
+}
+
+impl GameFailScene {
+    pub fn new(puzzle_size: PuzzleSize, puzzle_id: PuzzleId) -> Box<Self> {
+        let mut text = Text::default();
+        text.add(
+            TITLE,
+            (TEXT_X, TITLE_Y),
+            TEXT_WIDTH,
+            Align::Centre,
+            TextColour::Title,
+        )
+        .add(
+            MESSAGE,
+            (TEXT_X, MESSAGE_Y),
+            TEXT_WIDTH,
+            Align::Centre,
+            TextColour::Message,
+        );
+        for (&(x, y), name) in BUTTON_INDEXES.iter().zip(BUTTON_LABELS) {
+            let pos = (
+                x as i32 * TILE_SIZE + LABEL_OFFSET.0,
+                y as i32 * TILE_SIZE + LABEL_OFFSET.1,
+            );
+            let width = BUTTON_SIZE.0 as i32 * TILE_SIZE;
+            text.add(name, pos, width, Align::Centre, TextColour::Body);
+        }
+
+        Box::new(Self {
+            button_idx: 0,
+            puzzle_size,
+            puzzle_id,
+            backgrounds: background_stack([&bg_gfx::dots, &bg_gfx::fail]),
+            button_highlight_sprites: button_sprites(),
+            text,
+            button_highlight: Highlight::new(BUTTON_INDEXES[0].0, BUTTON_INDEXES[0].1),
+        })
+    }
+}
+
+impl Scene for GameFailScene {
+    fn init(&mut self, audio: &mut Audio) {
+        VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
+        audio.stop_music();
+    }
+
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction> {
+        self.button_highlight.update();
+        if let Some(dir) = Direction::from_recent_input(buttons) {
+            match dir {
+                Direction::Left => {
+                    if self.button_idx > 0 {
+                        self.button_idx -= 1;
+                    }
+                    let pos = BUTTON_INDEXES[self.button_idx];
+                    self.button_highlight.set_target(pos.0, pos.1);
+                    audio.play(Sfx::Cursor);
+                }
+                Direction::Right => {
+                    if self.button_idx < BUTTON_INDEXES.len() - 1 {
+                        self.button_idx += 1;
+                    }
+                    let pos = BUTTON_INDEXES[self.button_idx];
+                    self.button_highlight.set_target(pos.0, pos.1);
+                    audio.play(Sfx::Cursor);
+                }
+                _ => {}
+            }
+        } else if buttons.is_just_pressed(Button::A) {
+            audio.play(Sfx::Positive);
+            return Some(match self.button_idx {
+                0 => SceneAction::Game(self.puzzle_size, self.puzzle_id),
+                1 => SceneAction::puzzle_menu(self.puzzle_size, self.puzzle_id),
+                _ => panic!("invalid button_idx in fail: {}", self.button_idx),
+            });
+        }
+
+        None
+    }
+
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
+        self.text.show(graphics);
+
+        let shown = show_backgrounds(&self.backgrounds, graphics);
+
+        self.button_highlight
+            .show(graphics, &mut self.button_highlight_sprites, BUTTON_SIZE);
+
+        shown
+    }
+}
@@ -1,69 +1,49 @@
 use crate::button_highlight::Highlight;
 use crate::direction::Direction;
-use crate::gfx::{background_stack, button_sprites};
-use crate::puzzle_size::PuzzleSize;
-use crate::scenes::scene_game_puzzle::Guess;
-use crate::sfx::play_sfx;
-use crate::{SFX_CURSOR, SFX_NEGATIVE, SFX_POSITIVE, Scene, SceneAction, SceneMusic, bg_gfx};
+use crate::gfx::{background_stack, button_sprites, show_backgrounds};
+use crate::puzzle_size::{PuzzleId, PuzzleSize};
+use crate::sfx::{Audio, Sfx};
+use crate::{Scene, SceneAction, SceneResult, bg_gfx};
 use agb::display::GraphicsFrame;
 use agb::display::object::Object;
-use agb::display::tiled::{RegularBackground, VRAM_MANAGER};
+use agb::display::tiled::{BackgroundId, RegularBackground, VRAM_MANAGER};
 use agb::input::{Button, ButtonController};
-use agb::sound::mixer::{ChannelId, Mixer};
 use alloc::boxed::Box;
-use alloc::vec;
 use alloc::vec::Vec;
-use core::mem::swap;
 
-const BUTTON_INDEXES: [(u8, u8); 2] = [(11, 7), (11, 12)];
+const BUTTON_INDEXES: [(u8, u8); 3] = [(11, 5), (11, 9), (11, 13)];
 const BUTTON_SIZE: (u8, u8) = (7, 3);
 
+/// Opened over the game, which is resumed when this closes
 pub struct GamePauseScene {
     button_idx: usize,
-    backgrounds: [RegularBackground; 2],
+    /// None while a confirmation is open
+    backgrounds: Option<[RegularBackground; 2]>,
     button_highlight_sprites: [Object; 3],
     puzzle_size: PuzzleSize,
-    game_idx: usize,
-    grid_enabled: bool,
-    game_data: Vec<Vec<Guess>>,
-    sfx_enabled: bool,
+    puzzle_id: PuzzleId,
     button_highlight: Highlight,
 }
 
 impl GamePauseScene {
-    pub fn new(
-        puzzle_size: PuzzleSize,
-        game_idx: usize,
-        grid_enabled: bool,
-        game_data: Vec<Vec<Guess>>,
-        sfx_enabled: bool,
-    ) -> Box<Self> {
+    pub fn new(puzzle_size: PuzzleSize, puzzle_id: PuzzleId) -> Box<Self> {
         Box::new(Self {
             button_idx: 0,
-            backgrounds: background_stack([&bg_gfx::dots, &bg_gfx::pause]),
+            backgrounds: Some(build_backgrounds()),
             button_highlight_sprites: button_sprites(),
             puzzle_size,
-            grid_enabled,
-            game_data,
-            game_idx,
-            sfx_enabled,
+            puzzle_id,
             button_highlight: Highlight::new(BUTTON_INDEXES[0].0, BUTTON_INDEXES[0].1),
         })
     }
 }
 
 impl Scene for GamePauseScene {
-    fn init(
-        &mut self,
-        bgm: Option<(SceneMusic, ChannelId)>,
-        _mixer: &mut Mixer,
-    ) -> Option<(SceneMusic, ChannelId)> {
+    fn init(&mut self, _audio: &mut Audio) {
         VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
-
-        bgm
     }
 
-    fn update(&mut self, buttons: &ButtonController, mixer: &mut Mixer) -> Option<SceneAction> {
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction> {
         self.button_highlight.update();
         if let Some(dir) = Direction::from_recent_input(buttons) {
             match dir {
@@ -73,55 +53,55 @@ impl Scene for GamePauseScene {
                     }
                     let pos = BUTTON_INDEXES[self.button_idx];
                     self.button_highlight.set_target(pos.0, pos.1);
-                    play_sfx(mixer, self.sfx_enabled, SFX_CURSOR);
+                    audio.play(Sfx::Cursor);
                 }
                 Direction::Down => {
-                    if self.button_idx < 1 {
+                    if self.button_idx < BUTTON_INDEXES.len() - 1 {
                         self.button_idx += 1;
                     }
                     let pos = BUTTON_INDEXES[self.button_idx];
                     self.button_highlight.set_target(pos.0, pos.1);
-                    play_sfx(mixer, self.sfx_enabled, SFX_CURSOR);
+                    audio.play(Sfx::Cursor);
                 }
                 _ => {}
             }
+        } else if buttons.is_just_pressed(Button::A) && self.button_idx == 0 {
+            // the game plays a sound once it knows if there were mistakes
+            return Some(SceneAction::Close(SceneResult::Check));
         } else if buttons.is_just_pressed(Button::A) {
-            let mut empty = vec![vec![]];
-            swap(&mut empty, &mut self.game_data);
-            let negative = Box::new(SceneAction::RestoreGame(
-                self.puzzle_size,
-                self.game_idx,
-                self.grid_enabled,
-                empty,
-            ));
-            play_sfx(mixer, self.sfx_enabled, SFX_POSITIVE);
+            audio.play(Sfx::Positive);
             let positive = match self.button_idx {
-                0 => SceneAction::Game(self.puzzle_size, self.game_idx),
-                1 => SceneAction::PuzzleMenu(self.puzzle_size),
+                1 => SceneAction::RestartGame(self.puzzle_size, self.puzzle_id),
+                2 => SceneAction::puzzle_menu(self.puzzle_size, self.puzzle_id),
                 _ => panic!("invalid button_idx in pause: {}", self.button_idx),
             };
-            return Some(SceneAction::Confirm(Box::new(positive), negative));
+            return Some(SceneAction::Confirm(Box::new(positive)));
         } else if buttons.is_just_pressed(Button::START) {
-            play_sfx(mixer, self.sfx_enabled, SFX_NEGATIVE);
-            let mut empty = vec![vec![]];
-            swap(&mut empty, &mut self.game_data);
-            return Some(SceneAction::RestoreGame(
-                self.puzzle_size,
-                self.game_idx,
-                self.grid_enabled,
-                empty,
-            ));
+            audio.play(Sfx::Negative);
+            return Some(SceneAction::Close(SceneResult::Back));
         }
 
         None
     }
 
-    fn show(&mut self, graphics: &mut GraphicsFrame) {
-        self.backgrounds.iter().for_each(|bg| {
-            bg.show(graphics);
-        });
+    fn suspend(&mut self) {
+        self.backgrounds = None;
+    }
+
+    fn resume(&mut self, _result: SceneResult, _audio: &mut Audio) {
+        self.backgrounds = Some(build_backgrounds());
+    }
+
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
+        let shown = show_backgrounds(self.backgrounds.iter().flatten(), graphics);
 
         self.button_highlight
             .show(graphics, &mut self.button_highlight_sprites, BUTTON_SIZE);
+
+        shown
     }
 }
+
+fn build_backgrounds() -> [RegularBackground; 2] {
+    background_stack([&bg_gfx::dots, &bg_gfx::pause])
+}
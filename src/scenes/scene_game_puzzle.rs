@@ -1,23 +1,25 @@
 use crate::direction::Direction;
 use crate::gfx::TILE_SIZE;
+use crate::history::History;
 use crate::input::calc_cursor_position;
-use crate::puzzle_size::{CLUES_PER_COL, CLUES_PER_ROW, MAX_COLS, MAX_ROWS, Puzzle};
+use crate::nonos::generator::SEED_DIGITS;
+use crate::nonos::solver::{Cell, Solver, answer};
+use crate::puzzle_size::{BoardGrid, CLUES_PER_COL, CLUES_PER_ROW, Puzzle, PuzzleId};
 use crate::settings_data::HelpLevel;
-use crate::sfx::{init_bgm, play_sfx};
-use crate::{PuzzleSize, SFX_CURSOR, SFX_GAME, Scene, SceneAction, SceneMusic, bg_gfx, sprites};
+use crate::sfx::{Audio, Sfx};
+use crate::{PuzzleSize, Scene, SceneAction, SceneMusic, SceneResult, bg_gfx, sprites};
 use agb::display::object::{GraphicsMode, Object, Sprite};
 use agb::display::tiled::{
-    RegularBackground, RegularBackgroundSize, TileEffect, TileFormat, TileSetting, VRAM_MANAGER,
+    BackgroundId, RegularBackground, RegularBackgroundSize, TileEffect, TileFormat, TileSetting,
+    VRAM_MANAGER,
 };
 use agb::display::{GraphicsFrame, Priority};
 use agb::fixnum::{Num, vec2};
 use agb::input::{Button, ButtonController};
-use agb::sound::mixer::{ChannelId, Mixer};
 use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use alloc::{format, vec};
-use core::mem::swap;
 
 const INPUT_DELAY: u8 = 10;
 
@@ -29,6 +31,28 @@ const PER_LINE_OFFSET: (i32, i32) = (0, 2);
 const FIRST_COL_CLUE_POS: (usize, usize) = (8, 7);
 const FIRST_ROW_CLUE_POS: (usize, usize) = (7, 8);
 
+/// Most cells on screen at once, larger boards scroll
+const VIEW_SIZE: (usize, usize) = (22, 12);
+/// Cells kept between the cursor and the edge of the screen when the board can scroll
+const SCROLL_MARGIN: usize = 2;
+/// Cells between the grid lines drawn on boards that scroll
+const GRID_SPACING: usize = 5;
+const SCREEN_TILES: (usize, usize) = (30, 20);
+
+const GRID_LINE_VERT: u16 = 0;
+const GRID_LINE_HORZ: u16 = 1;
+const GRID_LINE_BOTH: u16 = 2;
+
+/// Lives at the start of a puzzle in lives mode
+pub const MAX_LIVES: u8 = 3;
+/// Top left of the hearts, in pixels
+const LIVES_POS: (i32, i32) = (34, 16);
+
+const MAX_SHOWN_SECONDS: u32 = 999 * 60 + 59;
+
+const PIECE_WRONG_FILLED: u16 = 3;
+const PIECE_WRONG_MARKED: u16 = 4;
+
 const NUMBERS_DEFAULT: usize = 0;
 const NUMBERS_COMPLETE: usize = 23;
 
@@ -37,6 +61,7 @@ enum GameState {
     WaitingForNoInput,
     Playing,
     Win,
+    Failed,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -54,30 +79,41 @@ impl Guess {
             Guess::Marked => 2,
         }
     }
+
+    pub fn from_index(idx: u8) -> Self {
+        match idx {
+            1 => Guess::Filled,
+            2 => Guess::Marked,
+            _ => Guess::Empty,
+        }
+    }
+}
+
+/// True if the guess disagrees with a cell of the answer
+fn is_mistake(guess: Guess, answer: Cell) -> bool {
+    matches!(
+        (guess, answer),
+        (Guess::Filled, Cell::Empty) | (Guess::Marked, Cell::Filled)
+    )
 }
 
-#[derive(Debug, Default)]
+/// Time spent on the puzzle
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
 pub struct Clock {
-    frames: u8,
-    seconds: u8,
-    minutes: u8,
+    frames: u32,
 }
 
 impl Clock {
     pub fn inc(&mut self) {
-        self.frames += 1;
-        if self.frames > 59 {
-            self.seconds += 1;
-            self.frames = 0;
-            if self.seconds > 59 {
-                self.seconds = 0;
-                self.minutes += 1;
-            }
-        }
+        self.frames = self.frames.saturating_add(1);
+    }
+
+    pub fn seconds(&self) -> u32 {
+        self.frames / 60
     }
 
     pub fn show(&self, graphics: &mut GraphicsFrame) {
-        format!("{:02}:{:02}", self.minutes, self.seconds)
+        format_time(self.seconds())
             .chars()
             .map(char_to_sprite)
             .map(Object::new)
@@ -92,131 +128,173 @@ impl Clock {
     }
 }
 
+/// Formats as minutes:seconds, stopping at 999:59
+pub fn format_time(seconds: u32) -> String {
+    let seconds = seconds.min(MAX_SHOWN_SECONDS);
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Layers of the game screen
+struct Backgrounds {
+    hints: RegularBackground,
+    title: RegularBackground,
+    pieces: RegularBackground,
+    grid: RegularBackground,
+}
+
+impl Backgrounds {
+    fn new(puzzle_size: PuzzleSize) -> Self {
+        let mut hints = RegularBackground::new(
+            Priority::P3,
+            RegularBackgroundSize::Background32x32,
+            TileFormat::FourBpp,
+        );
+
+        hints.fill_with(puzzle_size.bg_game());
+
+        let mut title = RegularBackground::new(
+            Priority::P2,
+            RegularBackgroundSize::Background32x32,
+            TileFormat::FourBpp,
+        );
+
+        title.fill_with(&bg_gfx::title);
+
+        let pieces = RegularBackground::new(
+            Priority::P0,
+            RegularBackgroundSize::Background32x32,
+            TileFormat::FourBpp,
+        );
+
+        Self {
+            hints,
+            title,
+            pieces,
+            grid: build_grid(puzzle_size),
+        }
+    }
+}
+
 pub struct GamePuzzleScene {
     cursor: (usize, usize),
-    background_hints: RegularBackground,
-    background_title: RegularBackground,
-    background_pieces: RegularBackground,
-    background_grid: RegularBackground,
+    /// Top left cell on screen
+    camera: (usize, usize),
+    /// Cells on screen, the whole board unless it's larger than `VIEW_SIZE`
+    view: (usize, usize),
+    /// None while the pause menu is open, so its VRAM can be used
+    backgrounds: Option<Backgrounds>,
     cursor_sprite: Object,
     puzzle_size: PuzzleSize,
     next_input_frame: u8,
     puzzle: Puzzle,
     guesses: Vec<Vec<Guess>>,
+    history: History,
+    mistakes: Vec<Vec<bool>>,
+    /// Set when placements are checked, either to flag mistakes or for lives mode
+    answer: Option<Vec<Vec<Cell>>>,
+    check_placement: bool,
+    /// None if not in lives mode
+    lives: Option<u8>,
+    /// Lives lost on the saved board, kept while lives mode is off so they aren't refilled
+    saved_lives_lost: u8,
     drag_mode: Option<(Guess, Guess)>,
     state: GameState,
-    game_idx: usize,
+    puzzle_id: PuzzleId,
     text: Vec<Vec<&'static Sprite>>,
     show_grid: bool,
-    music_enabled: bool,
-    sfx_enabled: bool,
     block: Object,
     clock: Clock,
+    /// Started from a saved board, so the clock only counts this session's time
+    resumed: bool,
     last_dpad: u8,
     row_complete: Vec<bool>,
     col_complete: Vec<bool>,
 }
 
 impl GamePuzzleScene {
-    #[allow(clippy::new_ret_no_self)]
+    #[allow(clippy::new_ret_no_self, clippy::too_many_arguments)]
     pub fn new(
         puzzle_size: PuzzleSize,
-        game_idx: usize,
-        init_game_data: Option<Vec<Vec<Guess>>>,
+        puzzle_id: PuzzleId,
+        progress: Option<Vec<Vec<Guess>>>,
+        lives_lost: u8,
         grid_enabled: bool,
-        music_enabled: bool,
-        sfx_enabled: bool,
         help_level: HelpLevel,
+        check_placement: bool,
+        lives_mode: bool,
     ) -> Box<dyn Scene> {
-        let mut background_hints = RegularBackground::new(
-            Priority::P3,
-            RegularBackgroundSize::Background32x32,
-            TileFormat::FourBpp,
-        );
-
-        background_hints.fill_with(puzzle_size.bg_game());
-
-        let mut background_title = RegularBackground::new(
-            Priority::P2,
-            RegularBackgroundSize::Background32x32,
-            TileFormat::FourBpp,
-        );
-
-        background_title.fill_with(&bg_gfx::title);
-
         let cursor_sprite = Object::new(sprites::SELECTOR.sprite(0));
 
-        let mut background_pieces = RegularBackground::new(
-            Priority::P0,
-            RegularBackgroundSize::Background32x32,
-            TileFormat::FourBpp,
-        );
-
-        let background_grid = build_grid(puzzle_size);
-
         let mut block = Object::new(sprites::BLOCK.sprite(0));
         block.set_graphics_mode(GraphicsMode::AlphaBlending);
 
-        let guesses = if let Some(data) = init_game_data {
-            if data.len() == puzzle_size.dimensions().1
-                && data[0].len() == puzzle_size.dimensions().0
-            {
-                data
+        let puzzle = puzzle_size.puzzle(puzzle_id);
+
+        let (width, height) = puzzle_size.dimensions();
+        let resumed = progress.is_some();
+        let guesses = if let Some(guesses) = progress {
+            if guesses.len() == height && guesses[0].len() == width {
+                guesses
             } else {
                 panic!(
-                    "invalid restore game data got {}x{} for {:?} {}",
-                    data.len(),
-                    data[0].len(),
+                    "invalid progress got {}x{} for {:?} {:?}",
+                    guesses.len(),
+                    guesses[0].len(),
                     puzzle_size,
-                    game_idx
+                    puzzle_id
                 )
             }
         } else {
-            let data =
-                vec![vec![Guess::Empty; puzzle_size.dimensions().0]; puzzle_size.dimensions().1];
-            pre_solve(
-                data,
-                help_level,
-                puzzle_size.games(game_idx).row_clues,
-                puzzle_size.games(game_idx).col_clues,
-            )
+            pre_solve(vec![vec![Guess::Empty; width]; height], help_level, &puzzle)
         };
+        let mistakes = vec![vec![false; width]; height];
 
-        for (y, row) in guesses.iter().enumerate() {
-            for (x, &guess) in row.iter().enumerate() {
-                set_piece(&mut background_pieces, (x, y), guess);
-            }
-        }
+        let answer = if check_placement || lives_mode {
+            Some(answer(&puzzle))
+        } else {
+            None
+        };
+        // at least one life is left, the board is cleared when the last one is lost
+        let lives = lives_mode.then(|| MAX_LIVES.saturating_sub(lives_lost).max(1));
 
-        let text = build_text(puzzle_size, game_idx);
+        let text = build_text(puzzle_size, puzzle_id);
 
-        let col_complete = vec![false; puzzle_size.dimensions().0];
-        let row_complete = vec![false; puzzle_size.dimensions().1];
+        let col_complete = vec![false; width];
+        let row_complete = vec![false; height];
 
-        Box::new(Self {
+        let mut scene = Box::new(Self {
             cursor: (0, 0),
-            background_hints,
-            background_title,
-            background_grid,
-            background_pieces,
+            camera: (0, 0),
+            view: (width.min(VIEW_SIZE.0), height.min(VIEW_SIZE.1)),
+            backgrounds: Some(Backgrounds::new(puzzle_size)),
             cursor_sprite,
             next_input_frame: 0,
             puzzle_size,
             block,
             guesses,
-            game_idx,
-            puzzle: puzzle_size.games(game_idx),
+            history: History::default(),
+            mistakes,
+            answer,
+            check_placement,
+            lives,
+            saved_lives_lost: lives_lost,
+            puzzle_id,
+            puzzle,
             drag_mode: None,
             state: GameState::WaitingForNoInput,
             text,
             show_grid: grid_enabled,
-            music_enabled,
-            sfx_enabled,
             clock: Clock::default(),
+            resumed,
             last_dpad: 0,
             row_complete,
             col_complete,
-        })
+        });
+        if check_placement {
+            scene.mark_mistakes();
+        }
+        scene.draw_board();
+        scene
     }
 }
 
@@ -225,14 +303,160 @@ impl GamePuzzleScene {
         if self.drag_mode.is_none()
             || self.drag_mode == Some((self.guesses[self.cursor.1][self.cursor.0], guess))
         {
-            self.drag_mode = Some((self.guesses[self.cursor.1][self.cursor.0], guess));
-            self.guesses[self.cursor.1][self.cursor.0] = guess;
-            set_piece(&mut self.background_pieces, self.cursor, guess);
-            self.refresh_row_clue(self.cursor.1);
-            self.refresh_col_clue(self.cursor.0);
+            let previous = self.guesses[self.cursor.1][self.cursor.0];
+            self.drag_mode = Some((previous, guess));
+            if guess == Guess::Filled && self.is_wrong_fill(self.cursor) {
+                // costs a life and marks the cell instead, not kept in the history
+                // so it can't be undone
+                self.lives = self.lives.map(|lives| lives.saturating_sub(1));
+                self.place(self.cursor, Guess::Marked);
+                return;
+            }
+            self.history.record(self.cursor, previous, guess);
+            self.place(self.cursor, guess);
+        }
+    }
+
+    fn lives_lost(&self) -> u8 {
+        match self.lives {
+            Some(lives) => MAX_LIVES - lives,
+            None => self.saved_lives_lost,
+        }
+    }
+
+    fn is_wrong_fill(&self, pos: (usize, usize)) -> bool {
+        self.lives.is_some()
+            && self
+                .answer
+                .as_ref()
+                .is_some_and(|answer| answer[pos.1][pos.0] == Cell::Empty)
+    }
+
+    fn place(&mut self, pos: (usize, usize), guess: Guess) {
+        let mistake = self.check_placement
+            && self
+                .answer
+                .as_ref()
+                .is_some_and(|answer| is_mistake(guess, answer[pos.1][pos.0]));
+        self.guesses[pos.1][pos.0] = guess;
+        self.mistakes[pos.1][pos.0] = mistake;
+        self.draw_piece(pos);
+        self.refresh_row_clue(pos.1);
+        self.refresh_col_clue(pos.0);
+    }
+
+    /// Screen column of board column `x`, in cells from the left of the view, None if it's
+    /// scrolled off
+    fn view_x(&self, x: usize) -> Option<usize> {
+        x.checked_sub(self.camera.0).filter(|&x| x < self.view.0)
+    }
+
+    fn view_y(&self, y: usize) -> Option<usize> {
+        y.checked_sub(self.camera.1).filter(|&y| y < self.view.1)
+    }
+
+    fn draw_piece(&mut self, pos: (usize, usize)) {
+        if let (Some(x), Some(y)) = (self.view_x(pos.0), self.view_y(pos.1))
+            && let Some(backgrounds) = &mut self.backgrounds
+        {
+            let guess = self.guesses[pos.1][pos.0];
+            let mistake = self.mistakes[pos.1][pos.0];
+            set_piece(&mut backgrounds.pieces, (x, y), guess, mistake);
         }
     }
 
+    /// Draws the cells under the camera and lines the grid up with them
+    fn draw_board(&mut self) {
+        for y in self.camera.1..self.camera.1 + self.view.1 {
+            for x in self.camera.0..self.camera.0 + self.view.0 {
+                self.draw_piece((x, y));
+            }
+        }
+        if let Some(backgrounds) = &mut self.backgrounds {
+            backgrounds.grid.set_scroll_pos((
+                (self.camera.0 % GRID_SPACING) as i32 * TILE_SIZE,
+                (self.camera.1 % GRID_SPACING) as i32 * TILE_SIZE,
+            ));
+        }
+    }
+
+    /// Draws the clues of the rows and columns on screen, clearing those of lines that
+    /// scrolled off
+    fn draw_clues(&mut self) {
+        let Some(backgrounds) = &mut self.backgrounds else {
+            return;
+        };
+        for i in 0..CLUES_PER_ROW {
+            for y in 0..self.view.1 {
+                let pos = (FIRST_ROW_CLUE_POS.0 - i, FIRST_ROW_CLUE_POS.1 + y);
+                clear_tile(&mut backgrounds.pieces, pos);
+            }
+        }
+        for i in 0..CLUES_PER_COL {
+            for x in 0..self.view.0 {
+                let pos = (FIRST_COL_CLUE_POS.0 + x, FIRST_COL_CLUE_POS.1 - i);
+                clear_tile(&mut backgrounds.pieces, pos);
+            }
+        }
+
+        for y in self.camera.1..self.camera.1 + self.view.1 {
+            self.redraw_row_clue(y, self.row_complete[y]);
+        }
+        for x in self.camera.0..self.camera.0 + self.view.0 {
+            self.redraw_col_clue(x, self.col_complete[x]);
+        }
+    }
+
+    /// Scrolls boards larger than the screen so the cursor stays away from the edges
+    fn follow_cursor(&mut self) {
+        let (width, height) = self.puzzle_size.dimensions();
+        let camera = (
+            follow(self.camera.0, self.cursor.0, self.view.0, width),
+            follow(self.camera.1, self.cursor.1, self.view.1, height),
+        );
+        if camera != self.camera {
+            self.camera = camera;
+            self.draw_board();
+            self.draw_clues();
+        }
+    }
+
+    /// Flags every guess that conflicts with the answer (see [answer])
+    ///
+    /// Returns the number of mistakes
+    fn mark_mistakes(&mut self) -> usize {
+        let answer = self.answer.get_or_insert_with(|| answer(&self.puzzle));
+        let mut count = 0;
+        for (y, row) in self.guesses.iter().enumerate() {
+            for (x, &guess) in row.iter().enumerate() {
+                let mistake = is_mistake(guess, answer[y][x]);
+                self.mistakes[y][x] = mistake;
+                count += mistake as usize;
+            }
+        }
+        count
+    }
+
+    fn undo(&mut self) -> bool {
+        let Some(changes) = self.history.undo() else {
+            return false;
+        };
+        for change in changes {
+            self.place(change.pos, change.from);
+        }
+        true
+    }
+
+    fn redo(&mut self) -> bool {
+        let Some(changes) = self.history.redo() else {
+            return false;
+        };
+        for change in changes {
+            self.place(change.pos, change.to);
+        }
+        true
+    }
+
     fn refresh_row_clue(&mut self, y: usize) {
         let complete = self.is_row_complete(y);
         if self.row_complete[y] == complete {
@@ -299,11 +523,17 @@ impl GamePuzzleScene {
             NUMBERS_DEFAULT
         };
 
-        let tile_x = (FIRST_COL_CLUE_POS.0 + x) as i32;
+        let Some(view_x) = self.view_x(x) else {
+            return;
+        };
+        let Some(backgrounds) = &mut self.backgrounds else {
+            return;
+        };
+        let tile_x = (FIRST_COL_CLUE_POS.0 + view_x) as i32;
 
         if self.puzzle.col_clues[x].iter().all(|&c| c == 0) {
             set_number_variant(
-                &mut self.background_pieces,
+                &mut backgrounds.pieces,
                 (tile_x, FIRST_COL_CLUE_POS.1 as i32),
                 0,
                 base,
@@ -317,7 +547,7 @@ impl GamePuzzleScene {
                 continue;
             }
             let tile_y = FIRST_COL_CLUE_POS.1 as i32 - count;
-            set_number_variant(&mut self.background_pieces, (tile_x, tile_y), num, base);
+            set_number_variant(&mut backgrounds.pieces, (tile_x, tile_y), num, base);
             count += 1;
         }
     }
@@ -329,11 +559,17 @@ impl GamePuzzleScene {
             NUMBERS_DEFAULT
         };
 
-        let tile_y = (FIRST_ROW_CLUE_POS.1 + y) as i32;
+        let Some(view_y) = self.view_y(y) else {
+            return;
+        };
+        let Some(backgrounds) = &mut self.backgrounds else {
+            return;
+        };
+        let tile_y = (FIRST_ROW_CLUE_POS.1 + view_y) as i32;
 
         if self.puzzle.row_clues[y].iter().all(|&c| c == 0) {
             set_number_variant(
-                &mut self.background_pieces,
+                &mut backgrounds.pieces,
                 (FIRST_ROW_CLUE_POS.0 as i32, tile_y),
                 0,
                 base,
@@ -347,7 +583,7 @@ impl GamePuzzleScene {
                 continue;
             }
             let tile_x = FIRST_ROW_CLUE_POS.0 as i32 - count;
-            set_number_variant(&mut self.background_pieces, (tile_x, tile_y), num, base);
+            set_number_variant(&mut backgrounds.pieces, (tile_x, tile_y), num, base);
             count += 1;
         }
     }
@@ -390,6 +626,22 @@ where
     runs == exp
 }
 
+/// Camera position along one axis that keeps `cursor` at least `SCROLL_MARGIN` cells from
+/// the edges of the view, moving as little as possible
+fn follow(camera: usize, cursor: usize, view: usize, board: usize) -> usize {
+    let min = (cursor + SCROLL_MARGIN + 1).saturating_sub(view);
+    let max = cursor.saturating_sub(SCROLL_MARGIN);
+    camera.clamp(min, max).min(board - view)
+}
+
+fn clear_tile(background: &mut RegularBackground, pos: (usize, usize)) {
+    background.set_tile(
+        (pos.0 as i32, pos.1 as i32),
+        &bg_gfx::numbers.tiles,
+        TileSetting::BLANK,
+    );
+}
+
 fn set_number_variant(background: &mut RegularBackground, pos: (i32, i32), num: u8, base: usize) {
     background.set_tile(
         pos,
@@ -399,26 +651,39 @@ fn set_number_variant(background: &mut RegularBackground, pos: (i32, i32), num:
 }
 
 impl Scene for GamePuzzleScene {
-    fn init(
-        &mut self,
-        bgm: Option<(SceneMusic, ChannelId)>,
-        mixer: &mut Mixer,
-    ) -> Option<(SceneMusic, ChannelId)> {
+    fn init(&mut self, audio: &mut Audio) {
         VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
 
         for y in 0..self.puzzle.height {
             self.row_complete[y] = self.is_row_complete(y);
-            self.redraw_row_clue(y, self.row_complete[y]);
         }
         for x in 0..self.puzzle.width {
             self.col_complete[x] = self.is_col_complete(x);
-            self.redraw_col_clue(x, self.col_complete[x]);
         }
+        self.draw_clues();
+
+        audio.music(SceneMusic::Game);
+    }
 
-        init_bgm(mixer, SFX_GAME, SceneMusic::Game, bgm, self.music_enabled)
+    fn suspend(&mut self) {
+        self.backgrounds = None;
     }
 
-    fn update(&mut self, buttons: &ButtonController, mixer: &mut Mixer) -> Option<SceneAction> {
+    fn resume(&mut self, result: SceneResult, audio: &mut Audio) {
+        if result == SceneResult::Check {
+            if self.mark_mistakes() == 0 {
+                audio.play(Sfx::Positive);
+            } else {
+                audio.play(Sfx::Negative);
+            }
+        }
+        // the buttons that closed the pause menu shouldn't place a piece
+        self.state = GameState::WaitingForNoInput;
+        self.backgrounds = Some(Backgrounds::new(self.puzzle_size));
+        self.draw_board();
+    }
+
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction> {
         self.clock.inc();
         match self.state {
             GameState::WaitingForNoInput => {
@@ -427,6 +692,7 @@ impl Scene for GamePuzzleScene {
                 }
             }
             GameState::Playing => {
+                let lives = self.lives;
                 let dpad: u8 = (buttons.is_pressed(Button::UP) as u8)
                     | ((buttons.is_pressed(Button::DOWN) as u8) << 1)
                     | ((buttons.is_pressed(Button::LEFT) as u8) << 2)
@@ -466,8 +732,9 @@ impl Scene for GamePuzzleScene {
                             self.drag_mode.is_none(),
                         );
                         if moved {
-                            play_sfx(mixer, self.sfx_enabled, SFX_CURSOR);
+                            audio.play(Sfx::Cursor);
                             self.next_input_frame = INPUT_DELAY;
+                            self.follow_cursor();
                         }
                     } else {
                         self.next_input_frame -= 1;
@@ -480,23 +747,45 @@ impl Scene for GamePuzzleScene {
                     self.set_piece(target);
                 }
 
+                if self.lives != lives {
+                    audio.play(Sfx::Negative);
+                    if self.lives == Some(0) {
+                        self.state = GameState::Failed;
+                        return None;
+                    }
+                }
+
                 if buttons.is_just_pressed(Button::SELECT) {
                     self.show_grid = !self.show_grid;
                 }
 
+                if self.drag_mode.is_none() {
+                    let changed = if buttons.is_just_pressed(Button::L) {
+                        self.undo()
+                    } else if buttons.is_just_pressed(Button::R) {
+                        self.redo()
+                    } else {
+                        false
+                    };
+                    if changed {
+                        audio.play(Sfx::Cursor);
+                    }
+                }
+
                 if buttons.is_just_pressed(Button::START) {
-                    let mut empty = vec![vec![]];
-                    swap(&mut empty, &mut self.guesses);
+                    self.drag_mode = None;
+                    self.history.end_stroke();
                     return Some(SceneAction::PauseMenu(
                         self.puzzle_size,
-                        self.game_idx,
-                        self.show_grid,
-                        empty,
+                        self.puzzle_id,
+                        self.guesses.clone(),
+                        self.lives_lost(),
                     ));
                 }
 
                 if !a_down && !b_down {
                     self.drag_mode = None;
+                    self.history.end_stroke();
                 }
 
                 self.validate_by_clues();
@@ -504,30 +793,37 @@ impl Scene for GamePuzzleScene {
             GameState::Win => {
                 return Some(SceneAction::Win(
                     self.puzzle_size,
-                    self.game_idx,
+                    self.puzzle_id,
+                    Box::new(self.puzzle.clone()),
                     self.show_grid,
+                    self.lives == Some(MAX_LIVES),
+                    (!self.resumed).then(|| self.clock.seconds()),
                 ));
             }
+            GameState::Failed => {
+                return Some(SceneAction::Fail(self.puzzle_size, self.puzzle_id));
+            }
         }
         None
     }
 
-    fn show(&mut self, graphics: &mut GraphicsFrame) {
-        let id = self.background_hints.show(graphics);
-        self.background_title.show(graphics);
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
+        let Some(backgrounds) = &self.backgrounds else {
+            return Vec::new();
+        };
+        let id = backgrounds.hints.show(graphics);
+        let mut shown = vec![id.into(), backgrounds.title.show(graphics).into()];
         if self.show_grid {
-            self.background_grid.show(graphics);
+            shown.push(backgrounds.grid.show(graphics).into());
         }
-        self.background_pieces.show(graphics);
+        shown.push(backgrounds.pieces.show(graphics).into());
 
-        let pos = (
-            (self.cursor.0 as i32 + BOARD_OFFSET.0) * TILE_SIZE,
-            (self.cursor.1 as i32 + BOARD_OFFSET.1) * TILE_SIZE,
-        );
-        self.cursor_sprite.set_pos(pos).show(graphics);
+        let highlight_x = ((self.cursor.0 - self.camera.0) as i32 + BOARD_OFFSET.0) * TILE_SIZE;
+        let highlight_y = ((self.cursor.1 - self.camera.1) as i32 + BOARD_OFFSET.1) * TILE_SIZE;
+        self.cursor_sprite
+            .set_pos((highlight_x, highlight_y))
+            .show(graphics);
 
-        let highlight_x = (self.cursor.0 as i32 + BOARD_OFFSET.0) * TILE_SIZE;
-        let highlight_y = (self.cursor.1 as i32 + BOARD_OFFSET.1) * TILE_SIZE;
         for i in 0..8 {
             let x = i * TILE_SIZE;
             self.block.set_pos((x, highlight_y)).show(graphics);
@@ -542,6 +838,15 @@ impl Scene for GamePuzzleScene {
 
         self.clock.show(graphics);
 
+        if let Some(lives) = self.lives {
+            for i in 0..MAX_LIVES {
+                let frame = if i < lives { 0 } else { 1 };
+                Object::new(sprites::HEART.sprite(frame))
+                    .set_pos(vec2(LIVES_POS.0 + i as i32 * TILE_SIZE, LIVES_POS.1))
+                    .show(graphics);
+            }
+        }
+
         for (y, line) in self.text.iter().enumerate() {
             for (x, &sprite) in line.iter().enumerate() {
                 Object::new(sprite)
@@ -556,6 +861,8 @@ impl Scene for GamePuzzleScene {
                     .show(graphics);
             }
         }
+
+        shown
     }
 }
 
@@ -566,37 +873,64 @@ fn build_grid(board_size: PuzzleSize) -> RegularBackground {
         TileFormat::FourBpp,
     );
 
-    match board_size {
-        PuzzleSize::_6x6 => {}
-        PuzzleSize::_8x8 => {
-            background.fill_with(&bg_gfx::grid_8x8);
-        }
-        PuzzleSize::_10x10 => {
-            background.fill_with(&bg_gfx::grid_10x10);
-        }
-        PuzzleSize::_12x12 => {
-            background.fill_with(&bg_gfx::grid_12x12);
+    match board_size.info().grid {
+        BoardGrid::None => {}
+        BoardGrid::Fixed(grid) => {
+            background.fill_with(grid);
         }
-        PuzzleSize::_20x10 => {
-            background.fill_with(&bg_gfx::grid_20x10);
-        }
-        PuzzleSize::_22x12 => {
-            background.fill_with(&bg_gfx::grid_22x12);
+        BoardGrid::Scrolling => return build_scrolling_grid(),
+    }
+
+    background
+}
+
+/// Lines every `GRID_SPACING` cells, drawn past the right and bottom of the screen so the
+/// layer can be scrolled to line up with the camera
+fn build_scrolling_grid() -> RegularBackground {
+    let mut background = RegularBackground::new(
+        Priority::P0,
+        RegularBackgroundSize::Background64x32,
+        TileFormat::FourBpp,
+    );
+
+    let is_line = |tile: usize, board_start: i32| {
+        let board_start = board_start as usize;
+        tile > board_start && (tile - board_start).is_multiple_of(GRID_SPACING)
+    };
+    for y in 0..SCREEN_TILES.1 + GRID_SPACING {
+        for x in 0..SCREEN_TILES.0 + GRID_SPACING {
+            let tile = match (is_line(x, BOARD_OFFSET.0), is_line(y, BOARD_OFFSET.1)) {
+                (true, true) => GRID_LINE_BOTH,
+                (true, false) => GRID_LINE_VERT,
+                (false, true) => GRID_LINE_HORZ,
+                (false, false) => continue,
+            };
+            background.set_tile(
+                (x as i32, y as i32),
+                &bg_gfx::grid_lines.tiles,
+                TileSetting::new(tile, TileEffect::default()),
+            );
         }
     }
 
     background
 }
 
-fn set_piece(background: &mut RegularBackground, pos: (usize, usize), guess: Guess) {
+/// `pos` is relative to the top left cell on screen
+fn set_piece(background: &mut RegularBackground, pos: (usize, usize), guess: Guess, wrong: bool) {
+    let tile = match (guess, wrong) {
+        (Guess::Filled, true) => PIECE_WRONG_FILLED,
+        (Guess::Marked, true) => PIECE_WRONG_MARKED,
+        _ => guess.index(),
+    };
     background.set_tile(
         (pos.0 as i32 + BOARD_OFFSET.0, pos.1 as i32 + BOARD_OFFSET.1),
         &bg_gfx::pieces.tiles,
-        TileSetting::new(guess.index(), TileEffect::default()),
+        TileSetting::new(tile, TileEffect::default()),
     );
 }
 
-fn build_text(board_size: PuzzleSize, game_idx: usize) -> Vec<Vec<&'static Sprite>> {
+fn build_text(board_size: PuzzleSize, puzzle_id: PuzzleId) -> Vec<Vec<&'static Sprite>> {
     let mut text = Vec::new();
 
     let mut row1 = vec![];
@@ -612,7 +946,13 @@ fn build_text(board_size: PuzzleSize, game_idx: usize) -> Vec<Vec<&'static Sprit
     row1.push(char_to_sprite('x'));
     process_str(board_size.dimensions().1.to_string(), &mut row1);
 
-    process_str((game_idx + 1).to_string(), &mut row2);
+    match puzzle_id {
+        PuzzleId::Set(idx) => process_str((idx + 1).to_string(), &mut row2),
+        PuzzleId::Endless(seed) => {
+            row2.push(char_to_sprite('#'));
+            process_str(format!("{seed:0width$}", width = SEED_DIGITS), &mut row2);
+        }
+    }
 
     text.push(row1);
     text.push(row2);
@@ -629,38 +969,46 @@ fn char_to_sprite(chr: char) -> &'static Sprite {
     }
 }
 
-fn pre_solve(
-    mut data: Vec<Vec<Guess>>,
-    help_level: HelpLevel,
-    rows_hints: &[[u8; CLUES_PER_ROW]; MAX_ROWS],
-    cols_hints: &[[u8; CLUES_PER_COL]; MAX_COLS],
-) -> Vec<Vec<Guess>> {
+fn pre_solve(mut data: Vec<Vec<Guess>>, help_level: HelpLevel, puzzle: &Puzzle) -> Vec<Vec<Guess>> {
     if help_level == HelpLevel::None {
         return data;
     }
 
+    if help_level.solvable() {
+        let mut solver = Solver::new(puzzle);
+        if solver.step().is_err() {
+            panic!("clues contradict for {puzzle:?}");
+        }
+        for (row, cells) in data.iter_mut().zip(solver.cells()) {
+            for (guess, cell) in row.iter_mut().zip(cells) {
+                *guess = match cell {
+                    Cell::Unknown => Guess::Empty,
+                    Cell::Filled => Guess::Filled,
+                    Cell::Empty => Guess::Marked,
+                };
+            }
+        }
+        return data;
+    }
+
     let height = data.len();
     let width = data[0].len();
 
     for (y, row) in data.iter_mut().enumerate().take(height) {
-        let first = rows_hints[y][0];
+        let first = puzzle.row_clues[y][0];
         if help_level.zeros() && first == 0 {
             row.fill(Guess::Marked);
         } else if help_level.full() && first == width as u8 {
             row.fill(Guess::Filled);
-        } else if help_level.solvable() && hint_calc(&rows_hints[y]) == width {
-            solve_row(&rows_hints[y], row, width);
         }
     }
 
-    for (x, col_hints) in cols_hints.iter().enumerate().take(width) {
+    for (x, col_hints) in puzzle.col_clues.iter().enumerate().take(width) {
         let first = col_hints[0];
         if help_level.zeros() && first == 0 {
             fill_col(&mut data, x, Guess::Marked);
         } else if help_level.full() && first == height as u8 {
             fill_col(&mut data, x, Guess::Filled);
-        } else if help_level.solvable() && hint_calc(col_hints) == width {
-            solve_col(&mut data, x, col_hints, height);
         }
     }
 
@@ -670,54 +1018,3 @@ fn pre_solve(
 fn fill_col(data: &mut [Vec<Guess>], x: usize, guess: Guess) {
     data.iter_mut().for_each(|row| row[x] = guess);
 }
-
-fn hint_calc(hints: &[u8]) -> usize {
-    let mut sum = 0;
-    let mut blocks = 0;
-    for &n in hints {
-        if n == 0 {
-            break;
-        }
-        sum += n as usize;
-        blocks += 1;
-    }
-
-    sum + blocks - 1
-}
-
-fn solve_row(hints: &[u8], row: &mut [Guess], width: usize) {
-    let mut offset = 0;
-    for &n in hints {
-        if n == 0 {
-            break;
-        }
-        let n = n as usize;
-        for slot in row.iter_mut().skip(offset).take(n) {
-            *slot = Guess::Filled;
-        }
-        offset += n;
-
-        if offset < width {
-            row[offset] = Guess::Marked;
-            offset += 1;
-        }
-    }
-}
-
-fn solve_col(data: &mut [Vec<Guess>], x: usize, hints: &[u8], height: usize) {
-    let mut offset = 0usize;
-    for &n in hints.iter() {
-        if n == 0 {
-            break;
-        }
-        let n = n as usize;
-        for slot in data.iter_mut().skip(offset).take(n) {
-            slot[x] = Guess::Filled;
-        }
-        offset += n;
-        if offset < height {
-            data[offset][x] = Guess::Marked;
-            offset += 1;
-        }
-    }
-}
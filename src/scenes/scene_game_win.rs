@@ -1,18 +1,27 @@
-use crate::puzzle_size::PuzzleSize;
-use crate::sfx::stop_bgm;
-use crate::{SFX_CONGRATS, Scene, SceneAction, SceneMusic, bg_gfx, sprites};
+use crate::nonos::generator::SEED_DIGITS;
+use crate::puzzle_size::{Puzzle, PuzzleId, PuzzleSize};
+use crate::scenes::scene_game_puzzle::format_time;
+use crate::sfx::{Audio, Sfx};
+use crate::text::{Align, Text, TextColour};
+use crate::{Scene, SceneAction, bg_gfx, sprites};
 use agb::display::object::{AffineMatrixObject, AffineMode, Object, ObjectAffine, Sprite};
 use agb::display::tiled::RegularBackgroundSize::Background32x32;
-use agb::display::tiled::{RegularBackground, TileFormat, VRAM_MANAGER};
+use agb::display::tiled::{BackgroundId, RegularBackground, TileFormat, VRAM_MANAGER};
 use agb::display::{AffineMatrix, GraphicsFrame, Priority};
 use agb::fixnum::{Num, Vector2D, num, vec2};
 use agb::input::{Button, ButtonController};
-use agb::sound::mixer::{ChannelId, Mixer, SoundChannel};
 use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
 use core::ops::Sub;
 
 const DURATION: i32 = 50;
 
+const TITLE_Y: i32 = 12;
+const TIME_Y: i32 = 126;
+const AUTHOR_Y: i32 = 140;
+
 //                                 C   o   n   g   r   a   t   u   l  a   t   i  o   n   s   !
 const LETTER_SPACING: [i32; 16] = [14, 12, 12, 14, 10, 14, 10, 14, 7, 12, 10, 8, 12, 14, 12, 0];
 
@@ -20,88 +29,133 @@ pub struct GameWinScene {
     anim_timer: u16,
     background: RegularBackground,
     puzzle_size: PuzzleSize,
-    music_enabled: bool,
-    puzzle_sprite: &'static Sprite,
+    puzzle_id: PuzzleId,
+    /// None for generated puzzles, which have no artwork
+    puzzle_sprite: Option<&'static Sprite>,
     scale: Num<i32, 16>,
+    time_text: Text,
+    /// Puzzle title and author
+    details_text: Text,
 }
 
 impl GameWinScene {
+    /// `puzzle` is the one just solved, so generated puzzles aren't built again.
+    /// `previous_best` is the best time before this win, None if it's the first. `seconds`
+    /// is None for boards resumed from a save, which only show the best time
     #[allow(clippy::new_ret_no_self)]
-    pub fn new(puzzle_size: PuzzleSize, game_idx: usize, music_enabled: bool) -> Box<dyn Scene> {
+    pub fn new(
+        puzzle_size: PuzzleSize,
+        puzzle_id: PuzzleId,
+        puzzle: &Puzzle,
+        seconds: Option<u32>,
+        previous_best: Option<u32>,
+    ) -> Box<dyn Scene> {
         let mut background =
             RegularBackground::new(Priority::P3, Background32x32, TileFormat::FourBpp);
         background.fill_with(&bg_gfx::win);
 
+        let text = match (seconds, previous_best) {
+            (None, None) => None,
+            (None, Some(best)) => Some(format!("Best {}", format_time(best))),
+            (Some(seconds), None) => Some(format!("Time {}", format_time(seconds))),
+            (Some(seconds), Some(best)) if seconds < best => {
+                Some(format!("Time {}   New best!", format_time(seconds)))
+            }
+            (Some(seconds), Some(best)) => Some(format!(
+                "Time {}   Best {}",
+                format_time(seconds),
+                format_time(best)
+            )),
+        };
+        let mut time_text = Text::default();
+        if let Some(text) = text {
+            time_text.add(&text, (0, TIME_Y), 240, Align::Centre, TextColour::Title);
+        }
+
+        let title = match puzzle_id {
+            PuzzleId::Set(_) => puzzle.title.map(String::from),
+            PuzzleId::Endless(seed) => Some(format!("Seed {seed:0width$}", width = SEED_DIGITS)),
+        };
+        let mut details_text = Text::default();
+        if let Some(title) = title {
+            details_text.add(&title, (0, TITLE_Y), 240, Align::Centre, TextColour::Title);
+        }
+        if let Some(author) = puzzle.author {
+            let text = format!("by {author}");
+            details_text.add(&text, (0, AUTHOR_Y), 240, Align::Centre, TextColour::Body);
+        }
+
         Box::new(Self {
             anim_timer: 0,
             background,
             puzzle_size,
-            music_enabled,
-            puzzle_sprite: puzzle_size.images().sprite(game_idx),
+            puzzle_id,
+            puzzle_sprite: puzzle_size.image(puzzle_id),
             scale: num!(2.0),
+            time_text,
+            details_text,
         })
     }
 }
 
 impl Scene for GameWinScene {
-    fn init(
-        &mut self,
-        bgm: Option<(SceneMusic, ChannelId)>,
-        mixer: &mut Mixer,
-    ) -> Option<(SceneMusic, ChannelId)> {
+    fn init(&mut self, audio: &mut Audio) {
         VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
-        if let Some(bgm) = bgm {
-            stop_bgm(mixer, bgm);
-        }
-        None
+        audio.stop_music();
     }
 
-    fn update(&mut self, buttons: &ButtonController, mixer: &mut Mixer) -> Option<SceneAction> {
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction> {
         self.scale = self.scale.sub(num!(0.01)).max(num!(0.5));
-        if self.music_enabled && self.anim_timer == 4 {
-            let mut music = SoundChannel::new_high_priority(SFX_CONGRATS);
-            music.stereo();
-            mixer.play_sound(music);
+        if self.anim_timer == 4 {
+            audio.play(Sfx::Congrats);
         }
         if self.anim_timer > DURATION as u16
             && (buttons.is_just_pressed(Button::A) || buttons.is_just_pressed(Button::B))
         {
-            return Some(SceneAction::PuzzleMenu(self.puzzle_size));
+            return Some(SceneAction::puzzle_menu(self.puzzle_size, self.puzzle_id));
         }
         None
     }
 
-    fn show(&mut self, graphics: &mut GraphicsFrame) {
-        let obj = ObjectAffine::new(
-            self.puzzle_sprite,
-            AffineMatrixObject::new(AffineMatrix::from_scale(Vector2D::new(
-                self.scale, self.scale,
-            ))),
-            AffineMode::AffineDouble,
-        );
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
+        let obj = self.puzzle_sprite.map(|sprite| {
+            ObjectAffine::new(
+                sprite,
+                AffineMatrixObject::new(AffineMatrix::from_scale(Vector2D::new(
+                    self.scale, self.scale,
+                ))),
+                AffineMode::AffineDouble,
+            )
+        });
 
         self.anim_timer += 1;
 
-        draw_bg_and_image(self.puzzle_size, obj, &self.background, graphics);
+        let shown = draw_bg_and_image(self.puzzle_size, obj, &self.background, graphics);
 
         draw_congrats(self.anim_timer, graphics);
+
+        if self.anim_timer > DURATION as u16 {
+            self.time_text.show(graphics);
+            self.details_text.show(graphics);
+        }
+
+        vec![shown]
     }
 }
 
 fn draw_bg_and_image(
     puzzle_size: PuzzleSize,
-    mut puzzle_sprite: ObjectAffine,
+    puzzle_sprite: Option<ObjectAffine>,
     background: &RegularBackground,
     graphics: &mut GraphicsFrame,
-) {
-    background.show(graphics);
-
-    let (x, y) = match puzzle_size {
-        PuzzleSize::_12x12 | PuzzleSize::_10x10 | PuzzleSize::_6x6 | PuzzleSize::_8x8 => (104, 36),
-        PuzzleSize::_20x10 | PuzzleSize::_22x12 => (88, 32),
-    };
+) -> BackgroundId {
+    let id = background.show(graphics).into();
 
-    puzzle_sprite.set_pos(vec2(x, y)).show(graphics);
+    if let Some(mut puzzle_sprite) = puzzle_sprite {
+        let (x, y) = puzzle_size.info().layout.win_pos;
+        puzzle_sprite.set_pos(vec2(x, y)).show(graphics);
+    }
+    id
 }
 
 fn draw_congrats(anim_timer: u16, graphics: &mut GraphicsFrame) {
@@ -1,45 +1,92 @@
 use crate::button_highlight::Highlight;
 use crate::direction::Direction;
-use crate::gfx::{TILE_SIZE, background_stack, lvl_button_sprites};
+use crate::gfx::{TILE_SIZE, background_stack, lvl_button_sprites, show_backgrounds};
 use crate::input::calc_cursor_position;
-use crate::puzzle_size::PuzzleSize;
-use crate::sfx::{init_bgm, play_sfx};
-use crate::{
-    SFX_CURSOR, SFX_MENU, SFX_NEGATIVE, SFX_POSITIVE, Scene, SceneAction, SceneMusic, bg_gfx,
-    sprites,
-};
-use agb::display::GraphicsFrame;
-use agb::display::object::Object;
-use agb::display::tiled::{RegularBackground, VRAM_MANAGER};
-use agb::fixnum::vec2;
+use crate::puzzle_size::{PuzzleId, PuzzleSize};
+use crate::scenes::scene_game_puzzle::format_time;
+use crate::sfx::{Audio, Sfx};
+use crate::text::{Align, Text, TextColour};
+use crate::{Scene, SceneAction, SceneMusic, bg_gfx, sprites};
+use agb::display::object::{AffineMatrixObject, AffineMode, Object, ObjectAffine};
+use agb::display::tiled::{BackgroundId, RegularBackground, VRAM_MANAGER};
+use agb::display::{AffineMatrix, GraphicsFrame};
+use agb::fixnum::{Num, vec2};
 use agb::input::{Button, ButtonController};
-use agb::sound::mixer::{ChannelId, Mixer};
 use alloc::boxed::Box;
-use alloc::vec;
+use alloc::format;
 use alloc::vec::Vec;
 
+/// Top left of the title and best time text, in pixels
+const DETAILS_POS: (i32, i32) = (8, 149);
+const DETAILS_WIDTH: i32 = 224;
+/// Leaves room for the difficulty and best time on the right
+const TITLE_WIDTH: i32 = 116;
+/// Top left of the first difficulty star, in pixels
+const DIFFICULTY_POS: (i32, i32) = (128, 150);
+const MAX_DIFFICULTY: u8 = 5;
+
+/// Order of the puzzles in the menu, switched with SELECT
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum PuzzleOrder {
+    #[default]
+    Number,
+    /// Easiest first, in number order within each rating
+    Difficulty,
+}
+
+impl PuzzleOrder {
+    fn next(self) -> Self {
+        match self {
+            PuzzleOrder::Number => PuzzleOrder::Difficulty,
+            PuzzleOrder::Difficulty => PuzzleOrder::Number,
+        }
+    }
+
+    /// Puzzle index shown by each button
+    fn puzzles(self, size: PuzzleSize) -> Vec<usize> {
+        let mut puzzles: Vec<usize> = (0..size.game_count()).collect();
+        if self == PuzzleOrder::Difficulty {
+            puzzles.sort_by_key(|&idx| size.games(idx).difficulty);
+        }
+        puzzles
+    }
+}
+
 pub struct PuzzleMenuScene {
     cursor: (usize, usize),
     backgrounds: [RegularBackground; 3],
     button_highlight_sprites: [Object; 3],
     size: PuzzleSize,
+    order: PuzzleOrder,
+    /// Puzzle index shown by each button
+    puzzles: Vec<usize>,
     empty_sprite: Vec<Object>,
     is_completed: Vec<bool>,
-    sfx_enabled: bool,
-    music_enabled: bool,
+    is_no_mistakes: Vec<bool>,
+    is_in_progress: Vec<bool>,
+    best_times: Vec<Option<u32>>,
+    /// Title and best time of the puzzle under the cursor
+    details_text: Text,
+    /// Difficulty of the puzzle under the cursor
+    difficulty: u8,
+    in_progress_sprite: Object,
+    no_mistakes_sprite: Object,
     button_highlight: Highlight,
 }
 
 impl PuzzleMenuScene {
-    #[allow(clippy::new_ret_no_self)]
+    #[allow(clippy::new_ret_no_self, clippy::too_many_arguments)]
     pub fn new(
         size: PuzzleSize,
+        order: PuzzleOrder,
         completed_games: &[u8],
-        music_enabled: bool,
-        sfx_enabled: bool,
+        is_no_mistakes: Vec<bool>,
+        is_in_progress: Vec<bool>,
+        best_times: Vec<Option<u32>>,
     ) -> Box<dyn Scene> {
         let is_completed: Vec<bool> = completed_games.iter().map(|&v| v > 0).collect();
-        let cursor = if let Some(pos) = is_completed.iter().position(|&v| !v) {
+        let puzzles = order.puzzles(size);
+        let cursor = if let Some(pos) = puzzles.iter().position(|&idx| !is_completed[idx]) {
             let x = pos % size.buttons()[0].len();
             let y = pos / size.buttons()[0].len();
             (x, y)
@@ -49,51 +96,91 @@ impl PuzzleMenuScene {
         let pos = size.buttons()[cursor.1][cursor.0];
         let button_highlight = Highlight::new(pos.0, pos.1);
 
-        let mut empty_sprite = vec![];
-        match size {
-            PuzzleSize::_12x12 | PuzzleSize::_8x8 | PuzzleSize::_10x10 | PuzzleSize::_6x6 => {
-                empty_sprite.push(Object::new(sprites::QUESTION_SQ.sprite(0)));
-            }
-            PuzzleSize::_20x10 | PuzzleSize::_22x12 => {
-                empty_sprite.push(Object::new(sprites::QUESTION_RECT_L.sprite(0)));
-                empty_sprite.push(Object::new(sprites::QUESTION_RECT_R.sprite(0)));
-            }
-        }
+        let empty_sprite = size
+            .info()
+            .layout
+            .empty
+            .iter()
+            .map(|tag| Object::new(tag.sprite(0)))
+            .collect();
 
-        Box::new(Self {
+        let mut scene = Box::new(Self {
             cursor,
             backgrounds: background_stack([&bg_gfx::dots, size.bg(), size.bg_title()]),
             button_highlight_sprites: lvl_button_sprites(),
             size,
+            order,
+            puzzles,
             empty_sprite,
             is_completed,
-            sfx_enabled,
-            music_enabled,
+            is_no_mistakes,
+            is_in_progress,
+            best_times,
+            details_text: Text::default(),
+            difficulty: 0,
+            in_progress_sprite: Object::new(sprites::IN_PROGRESS.sprite(0)),
+            no_mistakes_sprite: Object::new(sprites::NO_MISTAKES.sprite(0)),
             button_highlight,
-        })
+        });
+        scene.update_details();
+        scene
+    }
+
+    /// Puzzle index under the cursor
+    fn selected(&self) -> usize {
+        self.puzzles[self.cursor.1 * self.size.buttons()[0].len() + self.cursor.0]
+    }
+
+    fn update_details(&mut self) {
+        let idx = self.selected();
+        self.details_text.clear();
+        let puzzle = self.size.games(idx);
+        self.difficulty = puzzle.difficulty;
+        if let Some(title) = puzzle.title
+            && (self.is_completed[idx] || !puzzle.hidden)
+        {
+            self.details_text.add(
+                title,
+                DETAILS_POS,
+                TITLE_WIDTH,
+                Align::Left,
+                TextColour::Body,
+            );
+        }
+        if let Some(seconds) = self.best_times[idx] {
+            let text = format!("Best {}", format_time(seconds));
+            self.details_text.add(
+                &text,
+                DETAILS_POS,
+                DETAILS_WIDTH,
+                Align::Right,
+                TextColour::Body,
+            );
+        }
     }
 }
 
 impl Scene for PuzzleMenuScene {
-    fn init(
-        &mut self,
-        bgm: Option<(SceneMusic, ChannelId)>,
-        mixer: &mut Mixer,
-    ) -> Option<(SceneMusic, ChannelId)> {
+    fn init(&mut self, audio: &mut Audio) {
         VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
 
-        init_bgm(mixer, SFX_MENU, SceneMusic::Menu, bgm, self.music_enabled)
+        audio.music(SceneMusic::Menu);
     }
 
-    fn update(&mut self, buttons: &ButtonController, mixer: &mut Mixer) -> Option<SceneAction> {
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction> {
         self.button_highlight.update();
         if buttons.is_just_pressed(Button::A) {
-            play_sfx(mixer, self.sfx_enabled, SFX_POSITIVE);
-            let idx = self.cursor.1 * self.size.buttons()[0].len() + self.cursor.0;
-            return Some(SceneAction::Game(self.size, idx));
+            audio.play(Sfx::Positive);
+            return Some(SceneAction::Game(self.size, PuzzleId::Set(self.selected())));
         } else if buttons.is_just_pressed(Button::B) {
-            play_sfx(mixer, self.sfx_enabled, SFX_NEGATIVE);
+            audio.play(Sfx::Negative);
             return Some(SceneAction::MainMenu);
+        } else if buttons.is_just_pressed(Button::SELECT) {
+            audio.play(Sfx::Cursor);
+            return Some(SceneAction::PuzzleOrder(self.size, self.order.next()));
+        } else if buttons.is_just_pressed(Button::START) {
+            audio.play(Sfx::Positive);
+            return Some(SceneAction::EndlessMenu(self.size));
         }
         if calc_cursor_position(
             Direction::from_recent_input(buttons),
@@ -101,18 +188,17 @@ impl Scene for PuzzleMenuScene {
             (self.size.buttons()[0].len(), self.size.buttons().len()),
             true,
         ) {
-            play_sfx(mixer, self.sfx_enabled, SFX_CURSOR);
+            audio.play(Sfx::Cursor);
             let pos = self.size.buttons()[self.cursor.1][self.cursor.0];
             self.button_highlight.set_target(pos.0, pos.1);
+            self.update_details();
             return None;
         }
         None
     }
 
-    fn show(&mut self, graphics: &mut GraphicsFrame) {
-        self.backgrounds.iter().for_each(|bg| {
-            bg.show(graphics);
-        });
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
+        let shown = show_backgrounds(&self.backgrounds, graphics);
 
         self.button_highlight.show(
             graphics,
@@ -120,16 +206,39 @@ impl Scene for PuzzleMenuScene {
             self.size.button_size(),
         );
 
+        self.details_text.show(graphics);
+
+        for i in 0..MAX_DIFFICULTY {
+            let frame = if i < self.difficulty { 0 } else { 1 };
+            Object::new(sprites::DIFFICULTY.sprite(frame))
+                .set_pos(vec2(
+                    DIFFICULTY_POS.0 + i as i32 * TILE_SIZE,
+                    DIFFICULTY_POS.1,
+                ))
+                .show(graphics);
+        }
+
         for (iy, row) in self.size.buttons().iter().enumerate() {
             for (ix, (x, y)) in row.iter().enumerate() {
-                let i = iy * self.size.buttons()[0].len() + ix;
+                let i = self.puzzles[iy * self.size.buttons()[0].len() + ix];
                 let y = (*y as i32 + 1) * TILE_SIZE;
                 let start_x = (*x as i32 + 1) * TILE_SIZE;
+                // bottom right corner of the puzzle image
+                let marker_x =
+                    start_x + (self.empty_sprite.len() as i32 * TILE_SIZE * 2) - TILE_SIZE;
                 if self.is_completed[i] {
-                    Object::new(self.size.images().sprite(i))
-                        .set_pos(vec2(start_x, y))
-                        .show(graphics);
+                    if self.is_no_mistakes[i] {
+                        self.no_mistakes_sprite
+                            .set_pos(vec2(marker_x, y + TILE_SIZE))
+                            .show(graphics);
+                    }
+                    show_image(self.size, i, (start_x, y), graphics);
                 } else {
+                    if self.is_in_progress[i] {
+                        self.in_progress_sprite
+                            .set_pos(vec2(marker_x, y + TILE_SIZE))
+                            .show(graphics);
+                    }
                     for (i, sprite) in self.empty_sprite.iter_mut().enumerate() {
                         let x = start_x + ((i as i32 * TILE_SIZE) * 2);
                         sprite.set_pos(vec2(x, y)).show(graphics);
@@ -137,5 +246,32 @@ impl Scene for PuzzleMenuScene {
                 }
             }
         }
+
+        shown
     }
 }
+
+/// Draws the image of puzzle `idx` in the button at `pos`, shrunk if it's larger than the
+/// inside of the button
+fn show_image(size: PuzzleSize, idx: usize, pos: (i32, i32), graphics: &mut GraphicsFrame) {
+    let sprite = size.images().sprite(idx);
+    let (width, _) = sprite.size().to_width_height();
+    let inside = (size.button_size().0 as i32 - 1) * TILE_SIZE;
+    if width as i32 <= inside {
+        Object::new(sprite)
+            .set_pos(vec2(pos.0, pos.1))
+            .show(graphics);
+        return;
+    }
+
+    let scale = Num::<i32, 16>::new(width as i32) / inside;
+    // the image is drawn shrunk in the middle of its own size
+    let offset = (width as i32 - inside) / 2;
+    ObjectAffine::new(
+        sprite,
+        AffineMatrixObject::new(AffineMatrix::from_scale(vec2(scale, scale))),
+        AffineMode::Affine,
+    )
+    .set_pos(vec2(pos.0 - offset, pos.1 - offset))
+    .show(graphics);
+}
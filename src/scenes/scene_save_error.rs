@@ -0,0 +1,185 @@
+use crate::button_highlight::Highlight;
+use crate::direction::Direction;
+use crate::gfx::{TILE_SIZE, background_stack, button_sprites, show_backgrounds};
+use crate::sfx::{Audio, Sfx};
+use crate::text::{Align, Text, TextColour};
+use crate::{Scene, SceneAction, bg_gfx};
+use agb::display::GraphicsFrame;
+use agb::display::object::Object;
+use agb::display::tiled::{BackgroundId, RegularBackground, VRAM_MANAGER};
+use agb::input::{Button, ButtonController};
+use agb::save::Error;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+const BUTTON_INDEXES: [(u8, u8); 3] = [(2, 14), (11, 14), (20, 14)];
+const BUTTON_SIZE: (u8, u8) = (7, 3);
+const BUTTON_LABELS: [&str; 3] = ["Retry", "No save", "Reset"];
+/// Label position inside the button, in pixels
+const LABEL_OFFSET: (i32, i32) = (3, 10);
+
+const TITLE_Y: i32 = 16;
+const MESSAGE_Y: i32 = 40;
+const TEXT_WIDTH: i32 = 208;
+const TEXT_X: i32 = 16;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SaveFailure {
+    Read(SaveErrorCause),
+    Write(SaveErrorCause),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SaveErrorCause {
+    NoMedia,
+    Rejected,
+    TimedOut,
+    TooSmall,
+    Busy,
+    Unknown,
+}
+
+impl From<Error> for SaveErrorCause {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::NoMedia => SaveErrorCause::NoMedia,
+            Error::WriteError => SaveErrorCause::Rejected,
+            Error::OperationTimedOut => SaveErrorCause::TimedOut,
+            Error::OutOfBounds => SaveErrorCause::TooSmall,
+            Error::MediaInUse => SaveErrorCause::Busy,
+            _ => SaveErrorCause::Unknown,
+        }
+    }
+}
+
+impl SaveFailure {
+    fn message(self) -> &'static str {
+        let (SaveFailure::Read(cause) | SaveFailure::Write(cause)) = self;
+        match cause {
+            SaveErrorCause::NoMedia => {
+                "No save chip was found. Check this is the right version of the game for the cartridge."
+            }
+            SaveErrorCause::Rejected => "The save chip didn't accept the data.",
+            SaveErrorCause::TimedOut => "The save chip stopped responding.",
+            SaveErrorCause::TooSmall => "The save chip is too small for the save data.",
+            SaveErrorCause::Busy => "The save chip was busy.",
+            SaveErrorCause::Unknown => "Unknown error.",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            SaveFailure::Read(_) => "Unable to load save",
+            SaveFailure::Write(_) => "Unable to save",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SaveRecovery {
+    /// Try the failed read or write again
+    Retry(SaveFailure),
+    /// Keep playing but don't read or write save data until the next boot
+    WithoutSaving,
+    /// Replace the save with default settings and no progress
+    Reset,
+}
+
+pub struct SaveErrorScene {
+    button_idx: usize,
+    failure: SaveFailure,
+    backgrounds: [RegularBackground; 2],
+    button_highlight_sprites: [Object; 3],
+    text: Text,
+    button_highlight: Highlight,
+}
+
+impl SaveErrorScene {
+    pub fn new(failure: SaveFailure) -> Box<Self> {
+        let mut text = Text::default();
+        text.add(
+            failure.title(),
+            (TEXT_X, TITLE_Y),
+            TEXT_WIDTH,
+            Align::Centre,
+            TextColour::Title,
+        )
+        .add(
+            failure.message(),
+            (TEXT_X, MESSAGE_Y),
+            TEXT_WIDTH,
+            Align::Centre,
+            TextColour::Message,
+        );
+        for (&(x, y), name) in BUTTON_INDEXES.iter().zip(BUTTON_LABELS) {
+            let pos = (
+                x as i32 * TILE_SIZE + LABEL_OFFSET.0,
+                y as i32 * TILE_SIZE + LABEL_OFFSET.1,
+            );
+            let width = BUTTON_SIZE.0 as i32 * TILE_SIZE;
+            text.add(name, pos, width, Align::Centre, TextColour::Body);
+        }
+
+        Box::new(Self {
+            button_idx: 0,
+            failure,
+            backgrounds: background_stack([&bg_gfx::dots, &bg_gfx::save_error]),
+            button_highlight_sprites: button_sprites(),
+            text,
+            button_highlight: Highlight::new(BUTTON_INDEXES[0].0, BUTTON_INDEXES[0].1),
+        })
+    }
+}
+
+impl Scene for SaveErrorScene {
+    fn init(&mut self, _audio: &mut Audio) {
+        VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
+    }
+
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction> {
+        self.button_highlight.update();
+        if let Some(dir) = Direction::from_recent_input(buttons) {
+            match dir {
+                Direction::Left => {
+                    if self.button_idx > 0 {
+                        self.button_idx -= 1;
+                    }
+                    let pos = BUTTON_INDEXES[self.button_idx];
+                    self.button_highlight.set_target(pos.0, pos.1);
+                    audio.play(Sfx::Cursor);
+                }
+                Direction::Right => {
+                    if self.button_idx < BUTTON_INDEXES.len() - 1 {
+                        self.button_idx += 1;
+                    }
+                    let pos = BUTTON_INDEXES[self.button_idx];
+                    self.button_highlight.set_target(pos.0, pos.1);
+                    audio.play(Sfx::Cursor);
+                }
+                _ => {}
+            }
+        } else if buttons.is_just_pressed(Button::A) {
+            audio.play(Sfx::Positive);
+            let recovery = match self.button_idx {
+                0 => SaveRecovery::Retry(self.failure),
+                1 => SaveRecovery::WithoutSaving,
+                2 => SaveRecovery::Reset,
+                _ => panic!("invalid button_idx in save error: {}", self.button_idx),
+            };
+            return Some(SceneAction::SaveRecovery(recovery));
+        }
+
+        None
+    }
+
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
+        self.text.show(graphics);
+
+        let shown = show_backgrounds(&self.backgrounds, graphics);
+
+        self.button_highlight
+            .show(graphics, &mut self.button_highlight_sprites, BUTTON_SIZE);
+
+        shown
+    }
+}
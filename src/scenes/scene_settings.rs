@@ -1,22 +1,30 @@
 use crate::direction::Direction;
-use crate::gfx::{TILE_SIZE, background_stack};
+use crate::gfx::{TILE_SIZE, background_stack, show_backgrounds};
 use crate::settings_button_highlight::SettingsHighlight;
-use crate::settings_data::HelpLevel;
-use crate::sfx::{play_sfx, stop_bgm, update_bgm};
-use crate::{SFX_CURSOR, SFX_MENU, SFX_POSITIVE, Scene, SceneAction, SceneMusic, bg_gfx, sprites};
+use crate::settings_data::{HelpLevel, MAX_VOLUME};
+use crate::sfx::{Audio, Sfx};
+use crate::text::{Align, Text, TextColour};
+use crate::{Scene, SceneAction, SceneMusic, bg_gfx, sprites};
 use agb::display::object::Object;
-use agb::display::tiled::{RegularBackground, RegularBackgroundSize, TileFormat, VRAM_MANAGER};
+use agb::display::tiled::{
+    BackgroundId, RegularBackground, RegularBackgroundSize, TileFormat, VRAM_MANAGER,
+};
 use agb::display::{GraphicsFrame, Priority};
 use agb::fixnum::vec2;
 use agb::input::{Button, ButtonController};
-use agb::sound::mixer::{ChannelId, Mixer};
 use alloc::boxed::Box;
-use core::mem::swap;
+use alloc::vec::Vec;
+use alloc::{format, vec};
 
-const CHECKMARK_INDEXES: [(u8, u8); 2] = [(18, 6), (18, 10)];
-const SETTINGS_INDEXES: [(u8, u8); 4] = [(12, 14), (15, 14), (18, 14), (21, 14)];
+const CHECKMARK_INDEXES: [(u8, u8); 4] = [(18, 5), (18, 8), (18, 11), (18, 14)];
+const SETTINGS_INDEXES: [(u8, u8); 4] = [(12, 17), (15, 17), (18, 17), (21, 17)];
 
-const IDX_SETTINGS: usize = 2;
+const IDX_SFX: usize = 0;
+const IDX_MUSIC: usize = 1;
+const IDX_SETTINGS: usize = 4;
+
+/// Centres the volume text vertically in its box
+const VOLUME_TEXT_Y: i32 = 3;
 
 const WARNING_COUNT_MAX: u8 = 30;
 const WARNING_TIME: u8 = 8;
@@ -27,16 +35,19 @@ pub struct SettingsScene {
     warning_bg: RegularBackground,
     warning_count: u8,
     warning_time: u8,
-    music_enabled: bool,
-    sfx_enabled: bool,
-    bgm: Option<(SceneMusic, ChannelId)>,
+    /// Copied from the audio settings when opened, changes are passed on straight away
+    music_volume: u8,
+    sfx_volume: u8,
+    volume_text: Text,
+    check_placement: bool,
+    lives_mode: bool,
     help_level: HelpLevel,
     button_gfx: [Object; 4],
     button_highlight: SettingsHighlight,
 }
 
 impl SettingsScene {
-    pub fn new(music_enabled: bool, sfx_enabled: bool, help_level: HelpLevel) -> Box<Self> {
+    pub fn new(check_placement: bool, lives_mode: bool, help_level: HelpLevel) -> Box<Self> {
         let mut button_gfx = [
             Object::new(sprites::SETTINGS_TOP.sprite(0)),
             Object::new(sprites::SETTINGS_TOP.sprite(0)),
@@ -56,10 +67,12 @@ impl SettingsScene {
         Box::new(Self {
             button_idx: 0,
             backgrounds: background_stack([&bg_gfx::dots, &bg_gfx::settings]),
-            music_enabled,
-            sfx_enabled,
+            music_volume: MAX_VOLUME,
+            sfx_volume: MAX_VOLUME,
+            volume_text: Text::default(),
+            check_placement,
+            lives_mode,
             warning_bg,
-            bgm: None,
             warning_count: WARNING_COUNT_MAX,
             warning_time: 0,
             help_level,
@@ -72,35 +85,63 @@ impl SettingsScene {
     }
 }
 
-impl Scene for SettingsScene {
-    fn init(
-        &mut self,
-        mut bgm: Option<(SceneMusic, ChannelId)>,
-        mixer: &mut Mixer,
-    ) -> Option<(SceneMusic, ChannelId)> {
-        VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
+impl SettingsScene {
+    fn volume(&self, idx: usize) -> u8 {
+        if idx == IDX_SFX {
+            self.sfx_volume
+        } else {
+            self.music_volume
+        }
+    }
 
-        if self.music_enabled {
-            if bgm.is_some() {
-                swap(&mut self.bgm, &mut bgm);
-            } else {
-                self.bgm = Some(update_bgm(mixer, SFX_MENU, SceneMusic::Menu, None));
-            }
-        } else if let Some(bgm) = bgm {
-            stop_bgm(mixer, bgm);
+    /// Sets the volume of the selected row, playing a sound at the new sfx volume or
+    /// changing the music as it plays
+    fn set_volume(&mut self, audio: &mut Audio, volume: u8) {
+        if self.button_idx == IDX_SFX {
+            self.sfx_volume = volume;
+            audio.set_sfx_volume(volume);
+        } else {
+            self.music_volume = volume;
+            audio.set_music_volume(volume);
         }
+        audio.play(Sfx::Cursor);
+        self.layout_volumes();
+    }
 
-        None
+    /// Writes each volume in its box, where the other settings show a checkmark
+    fn layout_volumes(&mut self) {
+        self.volume_text.clear();
+        for idx in [IDX_SFX, IDX_MUSIC] {
+            let (x, y) = CHECKMARK_INDEXES[idx];
+            self.volume_text.add(
+                &format!("{}", self.volume(idx)),
+                (x as i32 * TILE_SIZE, y as i32 * TILE_SIZE + VOLUME_TEXT_Y),
+                TILE_SIZE * 2,
+                Align::Centre,
+                TextColour::Body,
+            );
+        }
+    }
+}
+
+impl Scene for SettingsScene {
+    fn init(&mut self, audio: &mut Audio) {
+        VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
+
+        self.music_volume = audio.music_volume();
+        self.sfx_volume = audio.sfx_volume();
+        self.layout_volumes();
+        audio.music(SceneMusic::Menu);
     }
 
-    fn update(&mut self, buttons: &ButtonController, mixer: &mut Mixer) -> Option<SceneAction> {
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction> {
         self.button_highlight.update();
         if let Some(dir) = Direction::from_recent_input(buttons) {
             match dir {
                 Direction::Up => {
                     if self.button_idx > 0 {
                         self.button_idx -= 1;
-                        play_sfx(mixer, self.sfx_enabled, SFX_CURSOR);
+                        audio.play(Sfx::Cursor);
                         let pos = CHECKMARK_INDEXES[self.button_idx];
                         self.button_highlight.set_target(pos.0, pos.1);
                     }
@@ -115,53 +156,64 @@ impl Scene for SettingsScene {
                             let pos = SETTINGS_INDEXES[self.help_level.to_byte() as usize];
                             self.button_highlight.set_target(pos.0, pos.1);
                         }
-                        play_sfx(mixer, self.sfx_enabled, SFX_CURSOR);
+                        audio.play(Sfx::Cursor);
                     }
                 }
                 Direction::Left => {
-                    if self.button_idx == IDX_SETTINGS && self.help_level > HelpLevel::None {
+                    if self.button_idx == IDX_SFX || self.button_idx == IDX_MUSIC {
+                        let volume = self.volume(self.button_idx);
+                        if volume > 0 {
+                            self.set_volume(audio, volume - 1);
+                        }
+                    } else if self.button_idx == IDX_SETTINGS && self.help_level > HelpLevel::None {
                         self.help_level = self.help_level.prev();
                         let pos = SETTINGS_INDEXES[self.help_level.to_byte() as usize];
                         self.button_highlight.set_target(pos.0, pos.1);
-                        play_sfx(mixer, self.sfx_enabled, SFX_CURSOR);
+                        audio.play(Sfx::Cursor);
                     }
                 }
                 Direction::Right => {
-                    if self.button_idx == IDX_SETTINGS && self.help_level < HelpLevel::Solvable {
+                    if self.button_idx == IDX_SFX || self.button_idx == IDX_MUSIC {
+                        let volume = self.volume(self.button_idx);
+                        if volume < MAX_VOLUME {
+                            self.set_volume(audio, volume + 1);
+                        }
+                    } else if self.button_idx == IDX_SETTINGS
+                        && self.help_level < HelpLevel::Solvable
+                    {
                         self.help_level = self.help_level.next();
                         let pos = SETTINGS_INDEXES[self.help_level.to_byte() as usize];
                         self.button_highlight.set_target(pos.0, pos.1);
-                        play_sfx(mixer, self.sfx_enabled, SFX_CURSOR);
+                        audio.play(Sfx::Cursor);
                     }
                 }
             }
         } else if buttons.is_just_pressed(Button::A) {
             match self.button_idx {
-                0 => {
-                    self.sfx_enabled = !self.sfx_enabled;
-                    play_sfx(mixer, self.sfx_enabled, SFX_POSITIVE);
+                IDX_SFX | IDX_MUSIC => {
+                    // A mutes, or turns back up to full
+                    let volume = if self.volume(self.button_idx) > 0 {
+                        0
+                    } else {
+                        MAX_VOLUME
+                    };
+                    self.set_volume(audio, volume);
                 }
-                1 => {
-                    self.music_enabled = !self.music_enabled;
-                    play_sfx(mixer, self.sfx_enabled, SFX_POSITIVE);
-                    let bgm = self.bgm.take();
-                    if self.music_enabled {
-                        self.bgm = Some(update_bgm(mixer, SFX_MENU, SceneMusic::Menu, bgm));
-                    } else if let Some(bgm) = bgm {
-                        stop_bgm(mixer, bgm);
-                    }
+                2 => {
+                    self.check_placement = !self.check_placement;
+                    audio.play(Sfx::Positive);
+                }
+                3 => {
+                    self.lives_mode = !self.lives_mode;
+                    audio.play(Sfx::Positive);
                 }
                 _ => {}
             }
         } else if buttons.is_just_pressed(Button::START) {
-            play_sfx(mixer, self.sfx_enabled, SFX_POSITIVE);
-            let bgm = self.bgm.take();
-            if let Some(bgm) = bgm {
-                stop_bgm(mixer, bgm);
-            }
+            audio.play(Sfx::Positive);
             return Some(SceneAction::SettingsClose(
-                self.music_enabled,
-                self.sfx_enabled,
+                self.check_placement,
+                self.lives_mode,
                 self.help_level,
             ));
         } else {
@@ -171,10 +223,6 @@ impl Scene for SettingsScene {
                     self.warning_time = WARNING_TIME;
                     self.warning_count = self.warning_count.saturating_sub(1);
                     if self.warning_count == 0 {
-                        let bgm = self.bgm.take();
-                        if let Some(bgm) = bgm {
-                            stop_bgm(mixer, bgm);
-                        }
                         return Some(SceneAction::DeleteSave);
                     }
                 } else {
@@ -188,19 +236,18 @@ impl Scene for SettingsScene {
         None
     }
 
-    fn show(&mut self, graphics: &mut GraphicsFrame) {
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
         if self.warning_count < WARNING_COUNT_MAX {
-            self.warning_bg.show(graphics);
+            let shown = self.warning_bg.show(graphics);
             let count = (WARNING_COUNT_MAX - self.warning_count) as i32;
             for x in 0..count {
                 Object::new(sprites::WARNING_BLOCK.sprite(0))
                     .set_pos(vec2(x * TILE_SIZE, 11 * TILE_SIZE) - vec2(0, 2))
                     .show(graphics);
             }
+            vec![shown.into()]
         } else {
-            self.backgrounds.iter().for_each(|bg| {
-                bg.show(graphics);
-            });
+            let shown = show_backgrounds(&self.backgrounds, graphics);
 
             let help_idx = self.help_level.to_byte() as usize;
             let help_pos = SETTINGS_INDEXES[help_idx];
@@ -214,12 +261,14 @@ impl Scene for SettingsScene {
                 ))
                 .show(graphics);
 
-            if self.sfx_enabled {
-                show_checkmark(CHECKMARK_INDEXES[0], graphics);
+            self.volume_text.show(graphics);
+            if self.check_placement {
+                show_checkmark(CHECKMARK_INDEXES[2], graphics);
             }
-            if self.music_enabled {
-                show_checkmark(CHECKMARK_INDEXES[1], graphics);
+            if self.lives_mode {
+                show_checkmark(CHECKMARK_INDEXES[3], graphics);
             }
+            shown
         }
     }
 }
@@ -0,0 +1,105 @@
+use crate::gfx::{background_stack, show_backgrounds};
+use crate::scenes::scene_game_puzzle::format_time;
+use crate::settings_data::Statistics;
+use crate::sfx::{Audio, Sfx};
+use crate::text::{Align, Text, TextColour};
+use crate::{Scene, SceneAction, SceneMusic, bg_gfx};
+use agb::display::GraphicsFrame;
+use agb::display::tiled::{BackgroundId, RegularBackground, VRAM_MANAGER};
+use agb::input::{Button, ButtonController};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+const TITLE_Y: i32 = 12;
+const FIRST_ROW_Y: i32 = 36;
+const ROW_HEIGHT: i32 = 16;
+/// Left edge of the sizes and totals columns, in pixels
+const COLUMN_X: [i32; 2] = [24, 128];
+const COLUMN_WIDTH: i32 = 88;
+
+/// Completion per size and totals across every puzzle
+pub struct StatisticsScene {
+    background: [RegularBackground; 1],
+    text: Text,
+}
+
+impl StatisticsScene {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(stats: Statistics) -> Box<dyn Scene> {
+        let mut text = Text::default();
+        text.add(
+            "Statistics",
+            (0, TITLE_Y),
+            240,
+            Align::Centre,
+            TextColour::Title,
+        );
+
+        let mut sizes: Vec<(String, String)> = stats
+            .completed
+            .iter()
+            .map(|&(size, completed, total)| {
+                let (w, h) = size.dimensions();
+                (format!("{w}x{h}"), format!("{completed}/{total}"))
+            })
+            .collect();
+        let completed: usize = stats.completed.iter().map(|&(_, count, _)| count).sum();
+        let total: usize = stats.completed.iter().map(|&(_, _, count)| count).sum();
+        sizes.push(("Total".to_string(), format!("{completed}/{total}")));
+
+        let optional_time =
+            |time: Option<u32>| time.map(format_time).unwrap_or_else(|| "-".to_string());
+        let totals = [
+            (
+                "Play time".to_string(),
+                format!(
+                    "{}h {:02}m",
+                    stats.play_minutes / 60,
+                    stats.play_minutes % 60
+                ),
+            ),
+            ("Fastest".to_string(), optional_time(stats.fastest)),
+            ("Slowest".to_string(), optional_time(stats.slowest)),
+            ("No help".to_string(), stats.no_help.to_string()),
+            ("Streak".to_string(), stats.streak.to_string()),
+            ("Best streak".to_string(), stats.best_streak.to_string()),
+        ];
+
+        for (x, rows) in COLUMN_X.into_iter().zip([&sizes[..], &totals[..]]) {
+            for (i, (label, value)) in rows.iter().enumerate() {
+                let y = FIRST_ROW_Y + i as i32 * ROW_HEIGHT;
+                text.add(label, (x, y), COLUMN_WIDTH, Align::Left, TextColour::Body)
+                    .add(value, (x, y), COLUMN_WIDTH, Align::Right, TextColour::Body);
+            }
+        }
+
+        Box::new(Self {
+            background: background_stack([&bg_gfx::dots]),
+            text,
+        })
+    }
+}
+
+impl Scene for StatisticsScene {
+    fn init(&mut self, audio: &mut Audio) {
+        VRAM_MANAGER.set_background_palettes(bg_gfx::PALETTES);
+
+        audio.music(SceneMusic::Menu);
+    }
+
+    fn update(&mut self, buttons: &ButtonController, audio: &mut Audio) -> Option<SceneAction> {
+        if buttons.is_just_pressed(Button::B) || buttons.is_just_pressed(Button::START) {
+            audio.play(Sfx::Negative);
+            return Some(SceneAction::MainMenu);
+        }
+        None
+    }
+
+    fn show(&mut self, graphics: &mut GraphicsFrame) -> Vec<BackgroundId> {
+        self.text.show(graphics);
+
+        show_backgrounds(&self.background, graphics)
+    }
+}
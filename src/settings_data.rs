@@ -1,25 +1,51 @@
-use crate::nonos::TOTAL_GAME_COUNT;
+use crate::nonos::{TOTAL_GAME_COUNT, TOTAL_PROGRESS_SIZE};
 use crate::puzzle_size::PuzzleSize;
+use crate::save_banks::SaveBanks;
+use crate::save_migration::migrate;
+use crate::scenes::scene_game_puzzle::Guess;
 use agb::eprintln;
 use agb::hash_map::HashMap;
+use agb::save::Error;
+use alloc::vec;
+use alloc::vec::Vec;
 
-const VERSION: u8 = 3;
+/// Bump when changing the layout, including starting to use a reserved byte or bit, and add
+/// a migration in save_migration
+pub const VERSION: u8 = 10;
 
 const SAVE_IDX_VERSION: usize = 0;
-const SAVE_IDX_GRID_6X6: usize = 1;
-const SAVE_IDX_GRID_8X8: usize = 2;
-const SAVE_IDX_GRID_10X10: usize = 3;
-const SAVE_IDX_GRID_12X12: usize = 4;
-const SAVE_IDX_GRID_20X10: usize = 5;
-const SAVE_IDX_GRID_22X12: usize = 6;
-const SAVE_IDX_MUSIC: usize = 7;
-const SAVE_IDX_SFX: usize = 8;
+//const GRID: usize = 1; //to 6, see SizeInfo::grid_save_idx
+//const RESERVED: usize = 7; //music on/off before v8
+//const RESERVED: usize = 8; //sfx on/off before v8
 const SAVE_IDX_HELP: usize = 9;
-//const RESERVED: usize = 10;
-//const RESERVED: usize = 11;
-const SAVE_IDX_GAME_DATA: usize = 12;
+const SAVE_IDX_MUSIC_VOLUME: usize = 10;
+const SAVE_IDX_SFX_VOLUME: usize = 11;
+const SAVE_IDX_CHECK_PLACEMENT: usize = 12;
+const SAVE_IDX_LIVES_MODE: usize = 13;
+const SAVE_IDX_PLAY_MINUTES: usize = 14; //and 15
+//const GRID: usize = 16; //25x25
+//const RESERVED: usize = 17;
+const SAVE_IDX_STREAK: usize = 18; //and 19
+const SAVE_IDX_BEST_STREAK: usize = 20; //and 21
+const SAVE_IDX_GAME_DATA: usize = 22;
+pub const SAVE_IDX_PROGRESS: usize = SAVE_IDX_GAME_DATA + TOTAL_GAME_COUNT;
+const SAVE_IDX_BEST_TIMES: usize = SAVE_IDX_PROGRESS + TOTAL_PROGRESS_SIZE;
+const SAVE_IDX_LIVES_LOST: usize = SAVE_IDX_BEST_TIMES + TOTAL_GAME_COUNT * 2;
 
-pub const SAVE_DATA_SIZE: usize = TOTAL_GAME_COUNT + SAVE_IDX_GAME_DATA;
+pub const SAVE_DATA_SIZE: usize = SAVE_IDX_LIVES_LOST + LIVES_LOST_SIZE;
+
+/// Lives lost on each in progress board, 2 bits per puzzle
+const LIVES_LOST_SIZE: usize = TOTAL_GAME_COUNT.div_ceil(4);
+
+/// Flags in each completed games byte
+const COMPLETED: u8 = 1;
+const COMPLETED_NO_MISTAKES: u8 = 1 << 1;
+const COMPLETED_NO_HELP: u8 = 1 << 2;
+
+const FRAMES_PER_MINUTE: u32 = 60 * 60;
+
+/// Loudest music and sound effect level, 0 is off
+pub const MAX_VOLUME: u8 = 10;
 
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -85,84 +111,400 @@ impl HelpLevel {
 
 pub struct SettingsData {
     pub grid_enabled: HashMap<PuzzleSize, bool>,
-    pub music_enabled: bool,
-    pub sfx_enabled: bool,
+    /// 0 to [MAX_VOLUME]
+    pub music_volume: u8,
+    /// 0 to [MAX_VOLUME]
+    pub sfx_volume: u8,
+    /// Show wrong cells as soon as they're placed
+    pub check_placement: bool,
+    /// Filling a wrong cell costs a life
+    pub lives_mode: bool,
     completed_games: [u8; TOTAL_GAME_COUNT],
+    progress: [u8; TOTAL_PROGRESS_SIZE],
+    /// In seconds, 0 if not completed
+    best_times: [u16; TOTAL_GAME_COUNT],
+    /// Kept with the in progress boards so resuming doesn't refill lives
+    lives_lost: [u8; LIVES_LOST_SIZE],
     pub help_level: HelpLevel,
+    /// Time the game has been running, only whole minutes are saved
+    play_frames: u32,
+    /// Puzzles won since the last one was failed or restarted
+    streak: u16,
+    best_streak: u16,
+}
+
+/// Overview of the players progress, for the statistics screen
+pub struct Statistics {
+    /// Completed and total puzzles for each size
+    pub completed: Vec<(PuzzleSize, usize, usize)>,
+    pub play_minutes: u32,
+    /// Quickest and slowest best times, in seconds
+    pub fastest: Option<u32>,
+    pub slowest: Option<u32>,
+    /// Puzzles completed with the help level set to none
+    pub no_help: usize,
+    /// Current and longest run of wins without failing or restarting a puzzle
+    pub streak: u16,
+    pub best_streak: u16,
+}
+
+impl Default for SettingsData {
+    fn default() -> Self {
+        SettingsData {
+            grid_enabled: PuzzleSize::all().map(|size| (size, true)).collect(),
+            music_volume: MAX_VOLUME,
+            sfx_volume: MAX_VOLUME,
+            check_placement: false,
+            lives_mode: false,
+            completed_games: [0; TOTAL_GAME_COUNT],
+            progress: [0; TOTAL_PROGRESS_SIZE],
+            best_times: [0; TOTAL_GAME_COUNT],
+            lives_lost: [0; LIVES_LOST_SIZE],
+            help_level: HelpLevel::Full,
+            play_frames: 0,
+            streak: 0,
+            best_streak: 0,
+        }
+    }
 }
 
 impl SettingsData {
+    /// Reads the newest save, upgrading it if it's from an older version
+    pub fn load(save: &mut SaveBanks) -> Result<SettingsData, Error> {
+        save.load().map(SettingsData::from_bytes)
+    }
+
+    pub fn save(&self, save: &mut SaveBanks) -> Result<(), Error> {
+        save.write(&self.as_bytes())
+    }
+
     pub fn from_bytes(bytes: [u8; SAVE_DATA_SIZE]) -> SettingsData {
-        if bytes[SAVE_IDX_VERSION] != VERSION {
-            eprintln!("Invalid save data (magic num)");
-            let mut grid_enabled = HashMap::new();
-            grid_enabled.insert(PuzzleSize::_6x6, true);
-            grid_enabled.insert(PuzzleSize::_8x8, true);
-            grid_enabled.insert(PuzzleSize::_10x10, true);
-            grid_enabled.insert(PuzzleSize::_12x12, true);
-            grid_enabled.insert(PuzzleSize::_20x10, true);
-            grid_enabled.insert(PuzzleSize::_22x12, true);
-            SettingsData {
-                grid_enabled,
-                music_enabled: true,
-                sfx_enabled: true,
-                completed_games: [0; TOTAL_GAME_COUNT],
-                help_level: HelpLevel::Full,
-            }
-        } else {
-            let mut grid_enabled = HashMap::new();
-            grid_enabled.insert(PuzzleSize::_6x6, bytes[SAVE_IDX_GRID_6X6] > 0);
-            grid_enabled.insert(PuzzleSize::_8x8, bytes[SAVE_IDX_GRID_8X8] > 0);
-            grid_enabled.insert(PuzzleSize::_10x10, bytes[SAVE_IDX_GRID_10X10] > 0);
-            grid_enabled.insert(PuzzleSize::_12x12, bytes[SAVE_IDX_GRID_12X12] > 0);
-            grid_enabled.insert(PuzzleSize::_20x10, bytes[SAVE_IDX_GRID_20X10] > 0);
-            grid_enabled.insert(PuzzleSize::_22x12, bytes[SAVE_IDX_GRID_22X12] > 0);
-            SettingsData {
-                grid_enabled,
-                help_level: HelpLevel::from_byte(bytes[SAVE_IDX_HELP]),
-                music_enabled: bytes[SAVE_IDX_MUSIC] > 0,
-                sfx_enabled: bytes[SAVE_IDX_SFX] > 0,
-                completed_games: bytes[SAVE_IDX_GAME_DATA..]
-                    .try_into()
-                    .expect("Invalid save data (slicing)"),
-            }
+        let Some(bytes) = migrate(&bytes) else {
+            eprintln!("Invalid save data (version {})", bytes[SAVE_IDX_VERSION]);
+            return SettingsData::default();
+        };
+
+        let mut best_times = [0; TOTAL_GAME_COUNT];
+        for (time, time_bytes) in best_times
+            .iter_mut()
+            .zip(bytes[SAVE_IDX_BEST_TIMES..].chunks_exact(2))
+        {
+            *time = u16::from_le_bytes([time_bytes[0], time_bytes[1]]);
+        }
+
+        SettingsData {
+            grid_enabled: PuzzleSize::all()
+                .map(|size| (size, bytes[size.info().grid_save_idx] > 0))
+                .collect(),
+            help_level: HelpLevel::from_byte(bytes[SAVE_IDX_HELP]),
+            music_volume: bytes[SAVE_IDX_MUSIC_VOLUME].min(MAX_VOLUME),
+            sfx_volume: bytes[SAVE_IDX_SFX_VOLUME].min(MAX_VOLUME),
+            check_placement: bytes[SAVE_IDX_CHECK_PLACEMENT] > 0,
+            lives_mode: bytes[SAVE_IDX_LIVES_MODE] > 0,
+            completed_games: bytes[SAVE_IDX_GAME_DATA..SAVE_IDX_PROGRESS]
+                .try_into()
+                .expect("Invalid save data (slicing)"),
+            progress: bytes[SAVE_IDX_PROGRESS..SAVE_IDX_BEST_TIMES]
+                .try_into()
+                .expect("Invalid save data (slicing progress)"),
+            best_times,
+            lives_lost: bytes[SAVE_IDX_LIVES_LOST..]
+                .try_into()
+                .expect("Invalid save data (slicing lives lost)"),
+            play_frames: u16::from_le_bytes([
+                bytes[SAVE_IDX_PLAY_MINUTES],
+                bytes[SAVE_IDX_PLAY_MINUTES + 1],
+            ]) as u32
+                * FRAMES_PER_MINUTE,
+            streak: u16::from_le_bytes([bytes[SAVE_IDX_STREAK], bytes[SAVE_IDX_STREAK + 1]]),
+            best_streak: u16::from_le_bytes([
+                bytes[SAVE_IDX_BEST_STREAK],
+                bytes[SAVE_IDX_BEST_STREAK + 1],
+            ]),
         }
     }
 
     pub fn as_bytes(&self) -> [u8; SAVE_DATA_SIZE] {
         let mut output = [0; SAVE_DATA_SIZE];
         output[SAVE_IDX_VERSION] = VERSION;
-        output[SAVE_IDX_GRID_6X6] = self.grid_enabled[&PuzzleSize::_6x6] as u8;
-        output[SAVE_IDX_GRID_8X8] = self.grid_enabled[&PuzzleSize::_8x8] as u8;
-        output[SAVE_IDX_GRID_10X10] = self.grid_enabled[&PuzzleSize::_10x10] as u8;
-        output[SAVE_IDX_GRID_12X12] = self.grid_enabled[&PuzzleSize::_12x12] as u8;
-        output[SAVE_IDX_GRID_20X10] = self.grid_enabled[&PuzzleSize::_20x10] as u8;
-        output[SAVE_IDX_GRID_22X12] = self.grid_enabled[&PuzzleSize::_22x12] as u8;
-        output[SAVE_IDX_MUSIC] = self.music_enabled as u8;
-        output[SAVE_IDX_SFX] = self.sfx_enabled as u8;
-        output[SAVE_IDX_GAME_DATA..].copy_from_slice(&self.completed_games);
+        for (&size, &enabled) in &self.grid_enabled {
+            output[size.info().grid_save_idx] = enabled as u8;
+        }
+        output[SAVE_IDX_MUSIC_VOLUME] = self.music_volume;
+        output[SAVE_IDX_SFX_VOLUME] = self.sfx_volume;
+        output[SAVE_IDX_CHECK_PLACEMENT] = self.check_placement as u8;
+        output[SAVE_IDX_LIVES_MODE] = self.lives_mode as u8;
+        output[SAVE_IDX_PLAY_MINUTES..SAVE_IDX_PLAY_MINUTES + 2]
+            .copy_from_slice(&(self.play_minutes().min(u16::MAX as u32) as u16).to_le_bytes());
+        output[SAVE_IDX_STREAK..SAVE_IDX_STREAK + 2].copy_from_slice(&self.streak.to_le_bytes());
+        output[SAVE_IDX_BEST_STREAK..SAVE_IDX_BEST_STREAK + 2]
+            .copy_from_slice(&self.best_streak.to_le_bytes());
+        output[SAVE_IDX_GAME_DATA..SAVE_IDX_PROGRESS].copy_from_slice(&self.completed_games);
+        output[SAVE_IDX_PROGRESS..SAVE_IDX_BEST_TIMES].copy_from_slice(&self.progress);
+        output[SAVE_IDX_LIVES_LOST..].copy_from_slice(&self.lives_lost);
+        for (time_bytes, time) in output[SAVE_IDX_BEST_TIMES..SAVE_IDX_LIVES_LOST]
+            .chunks_exact_mut(2)
+            .zip(self.best_times)
+        {
+            time_bytes.copy_from_slice(&time.to_le_bytes());
+        }
         output[SAVE_IDX_HELP] = self.help_level.to_byte();
         output
     }
 
-    pub fn set_completed(&mut self, size: PuzzleSize, idx: usize) {
-        self.completed_games[size.save_idx() + idx] = 1;
+    /// `no_mistakes` is only set by finishing in lives mode without losing a life,
+    /// and stays set if the puzzle is completed again with mistakes
+    pub fn set_completed(&mut self, size: PuzzleSize, idx: usize, no_mistakes: bool) {
+        let flags = &mut self.completed_games[size.save_idx() + idx];
+        *flags |= COMPLETED;
+        if no_mistakes {
+            *flags |= COMPLETED_NO_MISTAKES;
+        }
+        if self.help_level == HelpLevel::None {
+            *flags |= COMPLETED_NO_HELP;
+        }
     }
 
+    /// Non zero for completed puzzles
     pub fn is_completed_by_size(&self, size: PuzzleSize) -> &[u8] {
         &self.completed_games[size.save_idx()..size.save_idx() + size.game_count()]
     }
 
+    pub fn is_no_mistakes_by_size(&self, size: PuzzleSize) -> Vec<bool> {
+        self.is_completed_by_size(size)
+            .iter()
+            .map(|&flags| flags & COMPLETED_NO_MISTAKES != 0)
+            .collect()
+    }
+
+    pub fn best_time(&self, size: PuzzleSize, idx: usize) -> Option<u32> {
+        match self.best_times[size.save_idx() + idx] {
+            0 => None,
+            seconds => Some(seconds as u32),
+        }
+    }
+
+    pub fn best_times_by_size(&self, size: PuzzleSize) -> Vec<Option<u32>> {
+        (0..size.game_count())
+            .map(|idx| self.best_time(size, idx))
+            .collect()
+    }
+
+    /// Keeps `seconds` if it beats the stored time, returns true if it did
+    pub fn set_best_time(&mut self, size: PuzzleSize, idx: usize, seconds: u32) -> bool {
+        // 0 means no time so instant solves are rounded up
+        let seconds = seconds.clamp(1, u16::MAX as u32) as u16;
+        let best = &mut self.best_times[size.save_idx() + idx];
+        if *best == 0 || seconds < *best {
+            *best = seconds;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Called once per frame
+    pub fn inc_play_time(&mut self) {
+        self.play_frames = self.play_frames.saturating_add(1);
+    }
+
+    pub fn play_minutes(&self) -> u32 {
+        self.play_frames / FRAMES_PER_MINUTE
+    }
+
+    /// Adds a win to the current streak, generated puzzles count too
+    pub fn extend_streak(&mut self) {
+        self.streak = self.streak.saturating_add(1);
+        self.best_streak = self.best_streak.max(self.streak);
+    }
+
+    /// Called when a puzzle is failed or restarted
+    pub fn end_streak(&mut self) {
+        self.streak = 0;
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        let completed = PuzzleSize::all()
+            .map(|size| {
+                let count = self
+                    .is_completed_by_size(size)
+                    .iter()
+                    .filter(|&&flags| flags != 0)
+                    .count();
+                (size, count, size.game_count())
+            })
+            .collect();
+        let times = self.best_times.iter().filter(|&&t| t > 0);
+        Statistics {
+            completed,
+            play_minutes: self.play_minutes(),
+            fastest: times.clone().min().map(|&t| t as u32),
+            slowest: times.max().map(|&t| t as u32),
+            no_help: self
+                .completed_games
+                .iter()
+                .filter(|&&flags| flags & COMPLETED_NO_HELP != 0)
+                .count(),
+            streak: self.streak,
+            best_streak: self.best_streak,
+        }
+    }
+
+    /// Returns the stored board for a puzzle, or None if it hasn't been started
+    pub fn progress(&self, size: PuzzleSize, idx: usize) -> Option<Vec<Vec<Guess>>> {
+        if !self.is_in_progress(size, idx) {
+            return None;
+        }
+        let (w, h) = size.dimensions();
+        let board = self.progress_slice(size, idx);
+        let mut guesses = vec![vec![Guess::Empty; w]; h];
+        for (y, row) in guesses.iter_mut().enumerate() {
+            for (x, guess) in row.iter_mut().enumerate() {
+                let cell = y * w + x;
+                *guess = Guess::from_index((board[cell / 4] >> ((cell % 4) * 2)) & 0b11);
+            }
+        }
+        Some(guesses)
+    }
+
+    pub fn set_progress(&mut self, size: PuzzleSize, idx: usize, guesses: &[Vec<Guess>]) {
+        let w = size.dimensions().0;
+        let start = size.progress_idx() + idx * size.progress_size();
+        let board = &mut self.progress[start..start + size.progress_size()];
+        board.fill(0);
+        for (y, row) in guesses.iter().enumerate() {
+            for (x, &guess) in row.iter().enumerate() {
+                let cell = y * w + x;
+                board[cell / 4] |= (guess.index() as u8) << ((cell % 4) * 2);
+            }
+        }
+    }
+
+    pub fn clear_progress(&mut self, size: PuzzleSize, idx: usize) {
+        let start = size.progress_idx() + idx * size.progress_size();
+        self.progress[start..start + size.progress_size()].fill(0);
+        self.set_lives_lost(size, idx, 0);
+    }
+
+    /// Lives lost so far on an in progress board
+    pub fn lives_lost(&self, size: PuzzleSize, idx: usize) -> u8 {
+        let puzzle = size.save_idx() + idx;
+        (self.lives_lost[puzzle / 4] >> ((puzzle % 4) * 2)) & 0b11
+    }
+
+    /// Stores up to 3 lost lives, kept until the progress is cleared
+    pub fn set_lives_lost(&mut self, size: PuzzleSize, idx: usize, lost: u8) {
+        let puzzle = size.save_idx() + idx;
+        let shift = (puzzle % 4) * 2;
+        let byte = &mut self.lives_lost[puzzle / 4];
+        *byte = (*byte & !(0b11 << shift)) | (lost.min(0b11) << shift);
+    }
+
+    pub fn is_in_progress(&self, size: PuzzleSize, idx: usize) -> bool {
+        self.progress_slice(size, idx).iter().any(|&b| b != 0)
+    }
+
+    pub fn is_in_progress_by_size(&self, size: PuzzleSize) -> Vec<bool> {
+        (0..size.game_count())
+            .map(|idx| self.is_in_progress(size, idx))
+            .collect()
+    }
+
+    fn progress_slice(&self, size: PuzzleSize, idx: usize) -> &[u8] {
+        let start = size.progress_idx() + idx * size.progress_size();
+        &self.progress[start..start + size.progress_size()]
+    }
+
     pub fn reset(&mut self) {
-        self.grid_enabled.insert(PuzzleSize::_6x6, true);
-        self.grid_enabled.insert(PuzzleSize::_8x8, true);
-        self.grid_enabled.insert(PuzzleSize::_10x10, true);
-        self.grid_enabled.insert(PuzzleSize::_12x12, true);
-        self.grid_enabled.insert(PuzzleSize::_20x10, true);
-        self.grid_enabled.insert(PuzzleSize::_22x12, true);
-        self.music_enabled = true;
-        self.sfx_enabled = true;
-        self.completed_games = [0; TOTAL_GAME_COUNT];
-        self.help_level = HelpLevel::Full;
+        *self = SettingsData::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test_case]
+    fn no_mistakes_flag_is_kept(_gba: &mut agb::Gba) {
+        let mut data = SettingsData::default();
+        data.set_completed(PuzzleSize::_8x8, 4, false);
+        data.set_completed(PuzzleSize::_10x10, 2, true);
+        data.set_completed(PuzzleSize::_10x10, 2, false);
+        let data = SettingsData::from_bytes(data.as_bytes());
+
+        let no_mistakes = data.is_no_mistakes_by_size(PuzzleSize::_10x10);
+        assert!(no_mistakes[2]);
+        assert!(!no_mistakes[1]);
+        assert!(!data.is_no_mistakes_by_size(PuzzleSize::_8x8)[4]);
+    }
+
+    #[test_case]
+    fn best_times_are_kept(_gba: &mut agb::Gba) {
+        let mut data = SettingsData::default();
+        assert_eq!(data.best_time(PuzzleSize::_8x8, 4), None);
+        assert!(data.set_best_time(PuzzleSize::_8x8, 4, 300));
+        assert!(!data.set_best_time(PuzzleSize::_8x8, 4, 301));
+        assert!(data.set_best_time(PuzzleSize::_8x8, 4, 299));
+        assert!(data.set_best_time(PuzzleSize::_22x12, 11, 100_000));
+        let data = SettingsData::from_bytes(data.as_bytes());
+
+        assert_eq!(data.best_time(PuzzleSize::_8x8, 4), Some(299));
+        assert_eq!(data.best_time(PuzzleSize::_8x8, 3), None);
+        assert_eq!(
+            data.best_time(PuzzleSize::_22x12, 11),
+            Some(u16::MAX as u32)
+        );
+    }
+
+    #[test_case]
+    fn statistics_are_kept(_gba: &mut agb::Gba) {
+        let mut data = SettingsData::default();
+        data.set_completed(PuzzleSize::_6x6, 0, false);
+        data.set_completed(PuzzleSize::_8x8, 4, false);
+        data.help_level = HelpLevel::None;
+        data.set_completed(PuzzleSize::_8x8, 0, false);
+        data.set_best_time(PuzzleSize::_8x8, 0, 40);
+        data.set_best_time(PuzzleSize::_8x8, 4, 90);
+        for _ in 0..(61 * 60 * 60 + 30) {
+            data.inc_play_time();
+        }
+        let stats = SettingsData::from_bytes(data.as_bytes()).statistics();
+
+        assert_eq!(stats.play_minutes, 61);
+        assert_eq!(stats.fastest, Some(40));
+        assert_eq!(stats.slowest, Some(90));
+        assert_eq!(stats.no_help, 1);
+        assert_eq!(stats.completed[0], (PuzzleSize::_6x6, 1, 21));
+        assert_eq!(stats.completed[1], (PuzzleSize::_8x8, 2, 21));
+    }
+
+    #[test_case]
+    fn streaks_are_kept(_gba: &mut agb::Gba) {
+        let mut data = SettingsData::default();
+        for _ in 0..3 {
+            data.extend_streak();
+        }
+        data.end_streak();
+        data.extend_streak();
+        let stats = SettingsData::from_bytes(data.as_bytes()).statistics();
+
+        assert_eq!(stats.streak, 1);
+        assert_eq!(stats.best_streak, 3);
+    }
+
+    #[test_case]
+    fn lives_lost_are_kept_until_progress_cleared(_gba: &mut agb::Gba) {
+        let mut data = SettingsData::default();
+        data.set_progress(PuzzleSize::_8x8, 3, &vec![vec![Guess::Marked; 8]; 8]);
+        data.set_lives_lost(PuzzleSize::_8x8, 3, 2);
+        data.set_lives_lost(PuzzleSize::_8x8, 4, 1);
+        let mut data = SettingsData::from_bytes(data.as_bytes());
+
+        assert_eq!(data.lives_lost(PuzzleSize::_8x8, 3), 2);
+        assert_eq!(data.lives_lost(PuzzleSize::_8x8, 4), 1);
+        assert_eq!(data.lives_lost(PuzzleSize::_8x8, 5), 0);
+        data.clear_progress(PuzzleSize::_8x8, 3);
+        assert_eq!(data.lives_lost(PuzzleSize::_8x8, 3), 0);
+        assert_eq!(data.lives_lost(PuzzleSize::_8x8, 4), 1);
     }
 }
@@ -1,67 +1,184 @@
-use crate::SceneMusic;
-use agb::eprintln;
-use agb::sound::mixer::{ChannelId, Mixer, SoundChannel, SoundData};
+use crate::music::MusicPlayer;
+use crate::settings_data::{MAX_VOLUME, SettingsData};
+use crate::{
+    MUSIC_GAME, MUSIC_MENU, SFX_CONGRATS, SFX_CURSOR, SFX_NEGATIVE, SFX_POSITIVE, SceneMusic,
+};
+use agb::fixnum::Num;
+use agb::sound::mixer::{Frequency, Mixer, SoundChannel, SoundData};
+use agb_tracker::Track;
 
-pub fn play_sfx(mixer: &mut Mixer, sfx_enabled: bool, track: SoundData) {
-    if sfx_enabled {
-        let mut sfx = SoundChannel::new(track);
-        sfx.stereo();
-        mixer.play_sound(sfx);
-    }
+/// agb_tracker only plays modules at the right speed at 32768Hz
+pub const MIXER_FREQUENCY: Frequency = Frequency::Hz32768;
+
+/// The wavs are 18157Hz, slowed down to play at the right pitch with the tracker's mixer
+const WAV_PLAYBACK: Num<u32, 8> = Num::from_raw(18157 * 256 / 32768);
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Sfx {
+    Cursor,
+    Positive,
+    Negative,
+    /// Played once on the win screen, uses the music volume rather than the sound effect one
+    Congrats,
 }
 
-pub fn start_track(
-    mixer: &mut Mixer,
-    track: SoundData,
-    kind: SceneMusic,
-) -> (SceneMusic, ChannelId) {
-    let mut channel = SoundChannel::new_high_priority(track);
-    channel.should_loop().stereo();
-    match mixer.play_sound(channel) {
-        None => panic!("Unable to start bgm {:?}", kind),
-        Some(id) => (kind, id),
+impl Sfx {
+    fn track(self) -> SoundData {
+        match self {
+            Sfx::Cursor => SFX_CURSOR,
+            Sfx::Positive => SFX_POSITIVE,
+            Sfx::Negative => SFX_NEGATIVE,
+            Sfx::Congrats => SFX_CONGRATS,
+        }
     }
 }
 
-pub fn stop_bgm(mixer: &mut Mixer, current: (SceneMusic, ChannelId)) {
-    if let Some(channel) = mixer.channel(&current.1) {
-        channel.stop();
-    } else {
-        eprintln!("bgm channel missing {:?}", current.0);
+impl SceneMusic {
+    fn track(self) -> &'static Track {
+        match self {
+            SceneMusic::Menu => &MUSIC_MENU,
+            SceneMusic::Game => &MUSIC_GAME,
+        }
     }
 }
 
-pub fn update_bgm(
-    mixer: &mut Mixer,
-    track: SoundData,
-    kind: SceneMusic,
-    current: Option<(SceneMusic, ChannelId)>,
-) -> (SceneMusic, ChannelId) {
-    if let Some(current) = current {
-        if current.0 != kind {
-            stop_bgm(mixer, current);
-            start_track(mixer, track, kind)
-        } else {
-            current
+/// Owns the mixer, the music and sound effect volumes and the current bgm
+///
+/// Scenes ask for sounds and music, this decides whether they're actually played
+pub struct Audio<'gba> {
+    mixer: Mixer<'gba>,
+    /// 0 to [MAX_VOLUME], 0 is off
+    music_volume: u8,
+    sfx_volume: u8,
+    /// Music the current scene wants, kept while music is off so it can be started again
+    music: Option<SceneMusic>,
+    bgm: Option<(SceneMusic, MusicPlayer)>,
+}
+
+impl<'gba> Audio<'gba> {
+    pub fn new(mixer: Mixer<'gba>, settings: &SettingsData) -> Self {
+        Self {
+            mixer,
+            music_volume: settings.music_volume,
+            sfx_volume: settings.sfx_volume,
+            music: None,
+            bgm: None,
         }
-    } else {
-        start_track(mixer, track, kind)
     }
-}
 
-pub fn init_bgm(
-    mixer: &mut Mixer,
-    track: SoundData,
-    kind: SceneMusic,
-    bgm: Option<(SceneMusic, ChannelId)>,
-    music_enabled: bool,
-) -> Option<(SceneMusic, ChannelId)> {
-    if music_enabled {
-        Some(update_bgm(mixer, track, kind, bgm))
-    } else {
-        if let Some(bgm) = bgm {
-            stop_bgm(mixer, bgm);
+    pub fn play(&mut self, sfx: Sfx) {
+        let volume = match sfx {
+            Sfx::Congrats => self.music_volume,
+            _ => self.sfx_volume,
+        };
+        if volume > 0 {
+            let mut channel = match sfx {
+                Sfx::Congrats => SoundChannel::new_high_priority(sfx.track()),
+                _ => SoundChannel::new(sfx.track()),
+            };
+            channel
+                .stereo()
+                .volume(channel_volume(volume))
+                .playback(WAV_PLAYBACK);
+            self.mixer.play_sound(channel);
+        }
+    }
+
+    /// Starts `music` unless it's already playing, stopping any other track
+    pub fn music(&mut self, music: SceneMusic) {
+        self.music = Some(music);
+        self.update_bgm();
+    }
+
+    pub fn stop_music(&mut self) {
+        self.music = None;
+        self.update_bgm();
+    }
+
+    pub fn music_volume(&self) -> u8 {
+        self.music_volume
+    }
+
+    pub fn sfx_volume(&self) -> u8 {
+        self.sfx_volume
+    }
+
+    /// Changes the volume of the playing music from the next frame, stopping or restarting
+    /// it when turned off or back on
+    pub fn set_music_volume(&mut self, volume: u8) {
+        self.music_volume = volume.min(MAX_VOLUME);
+        self.update_bgm();
+    }
+
+    pub fn set_sfx_volume(&mut self, volume: u8) {
+        self.sfx_volume = volume.min(MAX_VOLUME);
+    }
+
+    /// Switches to the volumes of a loaded or reset save
+    pub fn use_settings(&mut self, settings: &SettingsData) {
+        self.sfx_volume = settings.sfx_volume;
+        self.set_music_volume(settings.music_volume);
+    }
+
+    pub fn frame(&mut self) {
+        if let Some((_, music)) = &mut self.bgm {
+            music.step(&mut self.mixer, channel_volume(self.music_volume));
         }
-        None
+        self.mixer.frame();
+    }
+
+    fn update_bgm(&mut self) {
+        let wanted = self.music.filter(|_| self.music_volume > 0);
+        if self.playing() == wanted {
+            return;
+        }
+        if let Some((_, mut music)) = self.bgm.take() {
+            music.stop(&mut self.mixer);
+        }
+        if let Some(kind) = wanted {
+            self.bgm = Some((kind, MusicPlayer::new(kind.track())));
+        }
+    }
+
+    fn playing(&self) -> Option<SceneMusic> {
+        self.bgm.as_ref().map(|(kind, _)| *kind)
+    }
+}
+
+/// Mixer volume for a 0 to [MAX_VOLUME] setting, [MAX_VOLUME] plays at the sound's own volume
+fn channel_volume(volume: u8) -> Num<i16, 8> {
+    Num::new(volume as i16) / MAX_VOLUME as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agb::sound::mixer::Frequency;
+
+    #[test_case]
+    fn muting_music_stops_and_restarts_scene_music(gba: &mut agb::Gba) {
+        let mut audio = Audio::new(
+            gba.mixer.mixer(Frequency::Hz10512),
+            &SettingsData::default(),
+        );
+        audio.music(SceneMusic::Menu);
+        assert_eq!(audio.playing(), Some(SceneMusic::Menu));
+
+        audio.set_music_volume(0);
+        assert_eq!(audio.playing(), None);
+        audio.music(SceneMusic::Game);
+        assert_eq!(audio.playing(), None);
+
+        audio.set_music_volume(3);
+        assert_eq!(audio.playing(), Some(SceneMusic::Game));
+        audio.set_music_volume(7);
+        assert_eq!(audio.playing(), Some(SceneMusic::Game));
+    }
+
+    #[test_case]
+    fn volume_scales_channel(_gba: &mut agb::Gba) {
+        assert_eq!(channel_volume(0), Num::new(0));
+        assert_eq!(channel_volume(MAX_VOLUME), Num::new(1));
+        assert_eq!(channel_volume(5), Num::new(1) / 2);
     }
 }
@@ -0,0 +1,93 @@
+use crate::FONT;
+use agb::display::font::{AlignmentKind, Layout, ObjectTextRenderer};
+use agb::display::object::{Object, Size};
+use agb::display::{GraphicsFrame, Palette16, Rgb, Rgb15};
+use alloc::vec::Vec;
+
+/// Widest group of letters drawn in one sprite
+const GROUP_WIDTH: i32 = 32;
+
+static TITLE_PALETTE: &Palette16 = &palette(Rgb::new(0, 107, 255).to_rgb15());
+static MESSAGE_PALETTE: &Palette16 = &palette(Rgb15::WHITE);
+static BODY_PALETTE: &Palette16 = &palette(Rgb15::BLACK);
+
+const fn palette(colour: Rgb15) -> Palette16 {
+    let mut colours = [Rgb15::BLACK; 16];
+    colours[1] = colour;
+    Palette16::new(colours)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TextColour {
+    /// Blue, matching the titles in the background art
+    Title,
+    /// White, for the messages on the fail and save error screens
+    Message,
+    /// Black, for everything else
+    Body,
+}
+
+impl TextColour {
+    fn palette(self) -> &'static Palette16 {
+        match self {
+            TextColour::Title => TITLE_PALETTE,
+            TextColour::Message => MESSAGE_PALETTE,
+            TextColour::Body => BODY_PALETTE,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Align {
+    Left,
+    Centre,
+    Right,
+}
+
+impl From<Align> for AlignmentKind {
+    fn from(value: Align) -> Self {
+        match value {
+            Align::Left => AlignmentKind::Left,
+            Align::Centre => AlignmentKind::Centre,
+            Align::Right => AlignmentKind::Right,
+        }
+    }
+}
+
+/// Strings drawn as sprites with [FONT]
+///
+/// Text is laid out once when added, so rebuild it (see [Text::clear]) when it changes
+#[derive(Default)]
+pub struct Text {
+    objects: Vec<Object>,
+}
+
+impl Text {
+    /// Adds `text` in a `width` pixel wide box with its top left at `pos`
+    ///
+    /// Lines longer than `width` wrap at spaces and `\n` starts a new line
+    pub fn add(
+        &mut self,
+        text: &str,
+        pos: (i32, i32),
+        width: i32,
+        align: Align,
+        colour: TextColour,
+    ) -> &mut Self {
+        let renderer = ObjectTextRenderer::new(colour.palette().into(), Size::S32x16);
+        for group in Layout::new(text, &FONT, align.into(), GROUP_WIDTH, width) {
+            self.objects.push(renderer.show(&group, pos));
+        }
+        self
+    }
+
+    pub fn clear(&mut self) {
+        self.objects.clear();
+    }
+
+    pub fn show(&self, graphics: &mut GraphicsFrame) {
+        for obj in &self.objects {
+            obj.show(graphics);
+        }
+    }
+}
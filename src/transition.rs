@@ -0,0 +1,176 @@
+use crate::SceneAction;
+use agb::display::GraphicsFrame;
+use agb::display::tiled::BackgroundId;
+use agb::fixnum::Num;
+
+/// agb has no mosaic API, so it's written straight to the register
+const MOSAIC: *mut u16 = 0x0400_004C as *mut u16;
+/// BG0CNT, BG1CNT to BG3CNT follow it
+const BG_CONTROL: *mut u16 = 0x0400_0008 as *mut u16;
+
+const BG_MOSAIC: u16 = 1 << 6;
+/// Fully dark as a [Num<u8, 4>]
+const MAX_DARKNESS: u16 = 16;
+const MAX_MOSAIC: u16 = 15;
+
+/// Effect shown while the main loop swaps scenes
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Transition {
+    /// Darken to black, taking this many frames each way
+    Fade(u16),
+    /// Pixelate the backgrounds while darkening, taking this many frames each way
+    Mosaic(u16),
+}
+
+impl Transition {
+    pub const QUICK_FADE: Transition = Transition::Fade(6);
+    pub const FADE: Transition = Transition::Fade(12);
+    pub const MOSAIC: Transition = Transition::Mosaic(20);
+
+    fn frames(self) -> u16 {
+        match self {
+            Transition::Fade(frames) | Transition::Mosaic(frames) => frames.max(1),
+        }
+    }
+}
+
+/// Fades out, hands back the action to swap scenes once the screen is black, then fades in
+pub struct SceneTransition {
+    effect: Transition,
+    /// Frames into the effect, counts back down while fading in
+    covered: u16,
+    /// Sent once faded out, None while fading in
+    pending: Option<SceneAction>,
+}
+
+impl SceneTransition {
+    pub fn new(effect: Transition, action: SceneAction) -> Self {
+        Self {
+            effect,
+            covered: 0,
+            pending: Some(action),
+        }
+    }
+
+    /// Advances a frame, returning the action on the frame the screen is fully covered
+    pub fn update(&mut self) -> Option<SceneAction> {
+        if self.pending.is_some() {
+            self.covered += 1;
+            if self.covered >= self.effect.frames() {
+                return self.pending.take();
+            }
+        } else {
+            self.covered = self.covered.saturating_sub(1);
+        }
+        None
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pending.is_none() && self.covered == 0
+    }
+
+    /// Darkness (0 to 16) and mosaic size (0 to 15) for this frame
+    fn levels(&self) -> (u16, u16) {
+        let frames = self.effect.frames();
+        let darkness = self.covered * MAX_DARKNESS / frames;
+        let mosaic = match self.effect {
+            Transition::Fade(_) => 0,
+            Transition::Mosaic(_) => self.covered * MAX_MOSAIC / frames,
+        };
+        (darkness, mosaic)
+    }
+
+    /// Darkens the shown backgrounds, objects and backdrop, replacing any blend the scene set
+    ///
+    /// Must be called after the scene is shown and before `GraphicsFrame::commit`
+    pub fn darken(&self, frame: &mut GraphicsFrame, backgrounds: &[BackgroundId]) {
+        let (darkness, _) = self.levels();
+        if darkness == 0 {
+            return;
+        }
+        let mut fade = frame.blend().darken(Num::from_raw(darkness as u8));
+        for &background in backgrounds {
+            fade.enable_background(background);
+        }
+        fade.enable_object().enable_backdrop();
+    }
+
+    /// Overrides the mosaic and background control registers for the next frame
+    ///
+    /// Must be called after `GraphicsFrame::commit` as agb rewrites the background controls
+    /// there, which also clears the effect once the transition stops being applied
+    pub fn apply_mosaic(&self) {
+        let (_, mosaic) = self.levels();
+        // SAFETY: these are the GBA's mosaic and background control registers, only
+        // written during vblank straight after agb has committed its own values
+        unsafe {
+            MOSAIC.write_volatile(mosaic | (mosaic << 4));
+            if mosaic > 0 {
+                for bg in 0..4 {
+                    let control = BG_CONTROL.add(bg);
+                    control.write_volatile(control.read_volatile() | BG_MOSAIC);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SceneResult;
+    use crate::puzzle_size::{PuzzleId, PuzzleSize};
+    use crate::scenes::scene_puzzle_menu::PuzzleOrder;
+    use alloc::boxed::Box;
+
+    fn start(action: SceneAction) -> SceneTransition {
+        let effect = action.transition().expect("action has no transition");
+        SceneTransition::new(effect, action)
+    }
+
+    #[test_case]
+    fn sends_action_once_covered_then_fades_in(_gba: &mut agb::Gba) {
+        let mut transition = start(SceneAction::MainMenu);
+        let frames = transition.effect.frames();
+
+        for _ in 1..frames {
+            assert_eq!(transition.update(), None);
+        }
+        assert_eq!(transition.update(), Some(SceneAction::MainMenu));
+        assert_eq!(transition.levels().0, MAX_DARKNESS);
+
+        for _ in 0..frames {
+            assert!(!transition.is_finished());
+            assert_eq!(transition.update(), None);
+        }
+        assert!(transition.is_finished());
+        assert_eq!(transition.levels(), (0, 0));
+    }
+
+    #[test_case]
+    fn mosaic_peaks_when_covered(_gba: &mut agb::Gba) {
+        let mut transition = start(SceneAction::Game(PuzzleSize::_6x6, PuzzleId::Set(0)));
+        assert_eq!(transition.effect, Transition::MOSAIC);
+
+        while transition.update().is_none() {}
+        assert_eq!(transition.levels(), (MAX_DARKNESS, MAX_MOSAIC));
+    }
+
+    #[test_case]
+    fn only_reordering_has_no_transition(_gba: &mut agb::Gba) {
+        let reorder = SceneAction::PuzzleOrder(PuzzleSize::_6x6, PuzzleOrder::Difficulty);
+        assert_eq!(reorder.transition(), None);
+        assert_eq!(
+            SceneAction::Close(SceneResult::Check).transition(),
+            Some(Transition::QUICK_FADE)
+        );
+        assert_eq!(
+            SceneAction::Close(SceneResult::Back).transition(),
+            Some(Transition::QUICK_FADE)
+        );
+        assert_eq!(
+            SceneAction::Confirm(Box::new(SceneAction::DeleteSave)).transition(),
+            Some(Transition::QUICK_FADE)
+        );
+    }
+}